@@ -0,0 +1,67 @@
+// Benchmarks decoding a large `get_results` reply into `TaskResult`s.
+
+use boinc_rpc::models::TaskResult;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+const RESULTS: usize = 1000;
+
+fn results_reply() -> treexml::Element {
+    let results = (0..RESULTS)
+        .map(|i| {
+            format!(
+                "<result>\
+                 <name>h1_{i}_0</name>\
+                 <wu_name>h1_{i}</wu_name>\
+                 <platform>x86_64-pc-linux-gnu</platform>\
+                 <version_num>104</version_num>\
+                 <plan_class>GW-opencl-nvidia-2</plan_class>\
+                 <project_url>https://einstein.phys.uwm.edu/</project_url>\
+                 <final_cpu_time>0.000000</final_cpu_time>\
+                 <final_elapsed_time>0.000000</final_elapsed_time>\
+                 <exit_status>0</exit_status>\
+                 <state>2</state>\
+                 <report_deadline>1700604800.000000</report_deadline>\
+                 <received_time>1700000000.000000</received_time>\
+                 <estimated_cpu_time_remaining>3600.000000</estimated_cpu_time_remaining>\
+                 <resources>0.5 NVIDIA GPU</resources>\
+                 <active_task>\
+                 <active_task_state>1</active_task_state>\
+                 <app_version_num>104</app_version_num>\
+                 <slot>{i}</slot>\
+                 <pid>4242</pid>\
+                 <scheduler_state>2</scheduler_state>\
+                 <checkpoint_cpu_time>120.000000</checkpoint_cpu_time>\
+                 <fraction_done>0.250000</fraction_done>\
+                 <current_cpu_time>130.000000</current_cpu_time>\
+                 <elapsed_time>140.000000</elapsed_time>\
+                 <swap_size>1000000.000000</swap_size>\
+                 <working_set_size_smoothed>500000.000000</working_set_size_smoothed>\
+                 <progress_rate>0.001000</progress_rate>\
+                 </active_task>\
+                 </result>"
+            )
+        })
+        .collect::<Vec<_>>()
+        .concat();
+    let reply = format!("<results>{results}</results>");
+    treexml::Document::parse(reply.as_bytes())
+        .unwrap()
+        .root
+        .unwrap()
+}
+
+fn decode_results(c: &mut Criterion) {
+    let reply = results_reply();
+    c.bench_function("decode 1000 results", |b| {
+        b.iter(|| {
+            black_box(&reply)
+                .children
+                .iter()
+                .map(TaskResult::from)
+                .collect::<Vec<_>>()
+        });
+    });
+}
+
+criterion_group!(benches, decode_results);
+criterion_main!(benches);
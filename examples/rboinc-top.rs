@@ -0,0 +1,122 @@
+// Example program for the rust-boinc-rpc crate.
+// This program connects to the BOINC client and shows a live, top-like view of
+// running tasks and recent log messages, refreshed every few seconds.
+
+use boinc_rpc::{models, Client, Error, Transport};
+use std::{collections::VecDeque, env, time::Duration};
+
+const REFRESH: Duration = Duration::from_secs(2);
+const MESSAGE_LINES: usize = 10;
+
+fn run_mode(mode: Option<models::RunMode>) -> &'static str {
+    match mode {
+        Some(models::RunMode::Always) => "always",
+        Some(models::RunMode::Auto) => "auto",
+        Some(models::RunMode::Never) => "never",
+        Some(models::RunMode::Restore) | None => "?",
+    }
+}
+
+fn render(
+    status: Option<&models::CcStatus>,
+    results: &[models::TaskResult],
+    messages: &VecDeque<models::Message>,
+) {
+    // Clear the screen and move the cursor home.
+    print!("\x1b[2J\x1b[H");
+
+    match status {
+        Some(status) => println!(
+            "CPU: {}  GPU: {}  Network: {}",
+            run_mode(status.task_run_mode()),
+            run_mode(status.gpu_run_mode()),
+            run_mode(status.network_run_mode()),
+        ),
+        None => println!("CPU: ?  GPU: ?  Network: ?"),
+    }
+    println!();
+
+    println!("{:>6}  {:>10}  TASK", "DONE", "ELAPSED");
+    let running = results
+        .iter()
+        .filter_map(|r| Some((r, r.active_task.as_ref()?)));
+    for (result, task) in running {
+        println!(
+            "{:>5.1}%  {:>9.0}s  {}",
+            task.fraction_done.unwrap_or_default() * 100.0,
+            task.elapsed_time.unwrap_or_default(),
+            result.name.as_deref().unwrap_or("?"),
+        );
+    }
+    println!();
+
+    for message in messages {
+        println!(
+            "[{}] {}",
+            message.project_name.as_deref().unwrap_or("---"),
+            message.body.as_deref().unwrap_or_default(),
+        );
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 2 {
+        eprintln!("Usage: {} <password>", args[0]);
+        std::process::exit(1);
+    }
+
+    let password = &args[1];
+
+    tokio::runtime::Runtime::new().unwrap().block_on(async {
+        let mut client = Client::new(Transport::new("127.0.0.1:31416", Some(password)));
+        let mut messages = VecDeque::with_capacity(MESSAGE_LINES);
+        let mut have_status = true;
+
+        loop {
+            // Older daemons may not know every RPC; show what we can.
+            let status = if have_status {
+                match client.get_cc_status().await {
+                    Ok(status) => Some(status),
+                    Err(Error::Unsupported { .. }) => {
+                        have_status = false;
+                        None
+                    }
+                    Err(e) => {
+                        eprintln!("Error: {:?}", e);
+                        return;
+                    }
+                }
+            } else {
+                None
+            };
+
+            let results = match client.get_results(true).await {
+                Ok(results) => results,
+                Err(e) => {
+                    eprintln!("Error: {:?}", e);
+                    return;
+                }
+            };
+
+            match client.get_new_messages().await {
+                Ok(new) => {
+                    for message in new {
+                        if messages.len() == MESSAGE_LINES {
+                            messages.pop_front();
+                        }
+                        messages.push_back(message);
+                    }
+                }
+                Err(Error::Unsupported { .. }) => {}
+                Err(e) => {
+                    eprintln!("Error: {:?}", e);
+                    return;
+                }
+            }
+
+            render(status.as_ref(), &results, &messages);
+            tokio::time::sleep(REFRESH).await;
+        }
+    });
+}
@@ -1,4 +1,5 @@
 #[derive(Clone, PartialEq, Eq, Debug)]
+#[non_exhaustive]
 pub enum Error {
     Connect(String),
     DataParse(String),
@@ -10,6 +11,88 @@ pub enum Error {
     Auth(String),
     InvalidURL(String),
     AlreadyAttached(String),
+    Timeout(String),
+    /// The connected daemon does not implement `rpc`: it is older than
+    /// `min_version` when known, or it rejected the request as unrecognized.
+    Unsupported {
+        rpc: String,
+        min_version: Option<String>,
+    },
+    /// Setting several run modes at once stopped part-way: the daemon
+    /// applied the components in `applied`, then rejected the next with
+    /// `error`.
+    PartiallyApplied {
+        applied: Vec<crate::models::Component>,
+        error: Box<Self>,
+    },
+}
+
+impl Error {
+    /// Name of the variant, as used in the serialized form.
+    #[must_use]
+    pub const fn kind(&self) -> &'static str {
+        match self {
+            Self::Connect(_) => "Connect",
+            Self::DataParse(_) => "DataParse",
+            Self::InvalidPassword(_) => "InvalidPassword",
+            Self::Daemon(_) => "Daemon",
+            Self::Null(_) => "Null",
+            Self::Network(_) => "Network",
+            Self::Status(_) => "Status",
+            Self::Auth(_) => "Auth",
+            Self::InvalidURL(_) => "InvalidURL",
+            Self::AlreadyAttached(_) => "AlreadyAttached",
+            Self::Timeout(_) => "Timeout",
+            Self::Unsupported { .. } => "Unsupported",
+            Self::PartiallyApplied { .. } => "PartiallyApplied",
+        }
+    }
+
+    /// The well-known BOINC error behind a [`Self::Status`], if it is one.
+    #[must_use]
+    pub const fn status_code(&self) -> Option<crate::models::BoincErrorCode> {
+        match self {
+            Self::Status(code) => crate::models::BoincErrorCode::from_i32(*code),
+            _ => None,
+        }
+    }
+}
+
+/// Serializes as `{ "kind": "Auth", "message": "..." }`, or
+/// `{ "kind": "Status", "code": -113 }` for status codes and
+/// `{ "kind": "Unsupported", "rpc": "...", "min_version": "..." }` (or
+/// `null`) for RPCs the daemon doesn't support. A partially applied request
+/// adds the `applied` components and the nested `error`.
+#[cfg(feature = "serde-error")]
+impl serde::Serialize for Error {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut s = serializer.serialize_struct("Error", 3)?;
+        s.serialize_field("kind", self.kind())?;
+        match self {
+            Self::Status(code) => s.serialize_field("code", code)?,
+            Self::Unsupported { rpc, min_version } => {
+                s.serialize_field("rpc", rpc)?;
+                s.serialize_field("min_version", min_version)?;
+            }
+            Self::PartiallyApplied { applied, error } => {
+                s.serialize_field("applied", applied)?;
+                s.serialize_field("error", error)?;
+            }
+            Self::Connect(message)
+            | Self::DataParse(message)
+            | Self::InvalidPassword(message)
+            | Self::Daemon(message)
+            | Self::Null(message)
+            | Self::Network(message)
+            | Self::Auth(message)
+            | Self::InvalidURL(message)
+            | Self::AlreadyAttached(message)
+            | Self::Timeout(message) => s.serialize_field("message", message)?,
+        }
+        s.end()
+    }
 }
 
 impl From<std::io::Error> for Error {
@@ -29,3 +112,39 @@ impl From<treexml::Error> for Error {
         Self::DataParse(format!("XML error: {e}"))
     }
 }
+
+impl From<quick_xml::Error> for Error {
+    fn from(e: quick_xml::Error) -> Self {
+        Self::DataParse(format!("XML error: {e}"))
+    }
+}
+
+#[cfg(all(test, feature = "serde-error"))]
+mod tests {
+    use super::Error;
+    use crate::models::Component;
+
+    #[test]
+    fn serialize_tagged() {
+        assert_eq!(
+            serde_json::to_value(Error::Auth("unauthorized".into())).unwrap(),
+            serde_json::json!({ "kind": "Auth", "message": "unauthorized" })
+        );
+        assert_eq!(
+            serde_json::to_value(Error::Status(-113)).unwrap(),
+            serde_json::json!({ "kind": "Status", "code": -113 })
+        );
+        assert_eq!(
+            serde_json::to_value(Error::PartiallyApplied {
+                applied: vec![Component::CPU],
+                error: Box::new(Error::Status(-1)),
+            })
+            .unwrap(),
+            serde_json::json!({
+                "kind": "PartiallyApplied",
+                "applied": ["cpu"],
+                "error": { "kind": "Status", "code": -1 },
+            })
+        );
+    }
+}
@@ -21,24 +21,66 @@
 mod errors;
 pub mod models;
 pub mod rpc;
+pub mod tracker;
 mod util;
 
-use crate::{errors::Error, rpc::DaemonStream};
+pub use errors::Error;
+pub use util::canonicalize_master_url;
+
+use crate::rpc::DaemonStream;
+use async_trait::async_trait;
 use std::{
+    collections::HashMap,
     fmt::Display,
     future::Future,
     pin::Pin,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
     task::{Context, Poll},
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    sync::Mutex,
 };
-use tokio::{net::TcpStream, sync::Mutex};
 use tower::ServiceExt;
 
+fn unsupported(rpc: &str) -> Error {
+    Error::Unsupported {
+        rpc: rpc.into(),
+        min_version: None,
+    }
+}
+
+/// Older daemons answer requests they don't know with an empty reply or an
+/// `unrecognized op` error; both become [`Error::Unsupported`].
+fn check_reply_not_empty(rpc: &str, data: &[treexml::Element]) -> Result<(), Error> {
+    if data.is_empty() {
+        return Err(unsupported(rpc));
+    }
+    Ok(())
+}
+
+/// Checks a reply for the ways the daemon reports failure.
+///
+/// An `<error_msg>` fails the reply even next to `<success/>`, whether at the
+/// top level or inside a reply element. One that sits beside an
+/// `<error_num>` is left to the caller, which interprets the number.
 fn verify_rpc_reply_contents(data: &[treexml::Element]) -> Result<bool, Error> {
+    let error_msg = |node: &treexml::Element| {
+        util::trimmed_optional(&util::any_text(node)).filter(|msg| !msg.is_empty())
+    };
     let mut success = false;
     for node in data {
         match &*node.name {
             "success" => success = true,
+            "error_msg" => {
+                if let Some(msg) = error_msg(node) {
+                    return Err(Error::Daemon(msg));
+                }
+            }
             "status" => {
                 return Err(Error::Status(
                     util::eval_node_contents(node).unwrap_or(9999),
@@ -48,45 +90,249 @@ fn verify_rpc_reply_contents(data: &[treexml::Element]) -> Result<bool, Error> {
                 return Err(Error::Auth(String::new()));
             }
             "error" => {
-                let error_msg = node
-                    .text
-                    .clone()
-                    .ok_or_else(|| Error::Daemon("Unknown error".into()))?;
+                // Some commands nest the failure as `<error><error_num>`.
+                if let Some(num) = node.children.iter().find(|c| c.name == "error_num") {
+                    return Err(Error::Status(util::eval_node_contents(num).unwrap_or(9999)));
+                }
+                let error_msg =
+                    error_msg(node).ok_or_else(|| Error::Daemon("Unknown error".into()))?;
 
+                if let Some(op) = error_msg.strip_prefix("unrecognized op") {
+                    return Err(Error::Unsupported {
+                        rpc: op.trim_start_matches(':').trim().into(),
+                        min_version: None,
+                    });
+                }
                 return match &*error_msg {
                     "unauthorized" | "Missing authenticator" => Err(Error::Auth(error_msg)),
-                    "Missing URL" => Err(Error::InvalidURL(error_msg)),
+                    "Missing URL" | "bad url" => Err(Error::InvalidURL(error_msg)),
                     "Already attached to project" => Err(Error::AlreadyAttached(error_msg)),
                     _ => Err(Error::DataParse(error_msg)),
                 };
             }
+            _ if !node.children.iter().any(|c| c.name == "error_num") => {
+                if let Some(msg) = node
+                    .children
+                    .iter()
+                    .filter(|c| c.name == "error_msg")
+                    .find_map(error_msg)
+                {
+                    return Err(Error::Daemon(msg));
+                }
+            }
             _ => {}
         }
     }
     Ok(success)
 }
 
+/// Whether `e` means the connection dropped, as opposed to the daemon or its
+/// reply being at fault.
+fn is_connection_lost(e: &Error) -> bool {
+    match e {
+        Error::Network(_) => true,
+        Error::Daemon(msg) => msg == "EOF",
+        _ => false,
+    }
+}
+
+/// Whether the request `rpc` (its root tag, e.g. `get_results`) only reads
+/// state, so [`Client`] may send it again after a dropped connection.
+///
+/// | Requests | Replayed |
+/// |---|---|
+/// | `get_*`, `exchange_versions`, `acct_mgr_info` | yes |
+/// | `*_poll`, e.g. `acct_mgr_rpc_poll` | yes |
+/// | `set_*`, `*_op`, `project_*`, `abort_result` and anything else | no |
+///
+/// Unknown requests are treated as mutating: a lost reply says nothing
+/// about whether the daemon acted on them, and replaying an abort or a
+/// detach must not happen twice.
+#[must_use]
+pub fn is_idempotent(rpc: &str) -> bool {
+    rpc.starts_with("get_")
+        || rpc.ends_with("_poll")
+        || matches!(rpc, "exchange_versions" | "acct_mgr_info")
+}
+
+fn results_request(active_only: bool) -> treexml::Element {
+    let mut node = treexml::Element::new("get_results");
+    if active_only {
+        let mut ao_node = treexml::Element::new("active_only");
+        ao_node.text = Some("1".into());
+        node.children.push(ao_node);
+    }
+    node
+}
+
+/// A stream [`Transport`] can carry requests over.
+trait StreamIo: AsyncRead + AsyncWrite + Send + Unpin {}
+
+impl<T: AsyncRead + AsyncWrite + Send + Unpin> StreamIo for T {}
+
+type BoxedStream = DaemonStream<Box<dyn StreamIo>>;
+
 type DaemonStreamFuture =
-    Pin<Box<dyn Future<Output = Result<DaemonStream<TcpStream>, Error>> + Send + Sync + 'static>>;
+    Pin<Box<dyn Future<Output = Result<BoxedStream, Error>> + Send + Sync + 'static>>;
+
+fn connect(addr: String, password: Option<String>) -> DaemonStreamFuture {
+    Box::pin(async move {
+        let conn = DaemonStream::connect(addr, password).await?;
+        Ok(conn.map_io(|io| Box::new(io) as Box<dyn StreamIo>))
+    })
+}
 
 enum ConnState {
-    Connecting(DaemonStreamFuture),
-    Ready(DaemonStream<TcpStream>),
+    /// Nothing allocated yet; connects on the next request.
+    Idle {
+        addr: String,
+        password: Option<String>,
+    },
+    Ready(BoxedStream),
     Error(Error),
 }
 
+type ConnSlot = Arc<Mutex<Option<ConnState>>>;
+
+/// Connection to a BOINC daemon.
+///
+/// Clones share the same underlying connections. Each request is routed to
+/// the next connection in turn and requests on one connection are serialized.
+/// A connection that fails, or fails to open, is retried on its next use.
+///
+/// `Transport` and its response futures are `Send`, so a `Client<Transport>`
+/// can be moved into `tokio::spawn`. It is not `Sync`: every request needs
+/// `&mut self`, so share it between tasks by cloning instead.
 pub struct Transport {
-    state: Arc<Mutex<Option<ConnState>>>,
+    /// Address and password to reconnect with, unless built from a stream.
+    target: Option<(String, Option<String>)>,
+    slots: Arc<[ConnSlot]>,
+    next_slot: Arc<AtomicUsize>,
+    /// Connection picked by `poll_ready` for the next `call`.
+    slot: usize,
+}
+
+impl Clone for Transport {
+    fn clone(&self) -> Self {
+        Self {
+            target: self.target.clone(),
+            slots: self.slots.clone(),
+            next_slot: self.next_slot.clone(),
+            slot: 0,
+        }
+    }
 }
 
 impl Transport {
     pub fn new<A: Display, P: Display>(addr: A, password: Option<P>) -> Self {
+        Self::with_connections(addr, password, 1)
+    }
+
+    /// Like [`Self::new`], but holds only the address and password until the
+    /// first request, for tools that create a transport per known host and
+    /// may never query most of them.
+    pub fn new_lazy<A: Display, P: Display>(addr: A, password: Option<P>) -> Self {
+        let addr = addr.to_string();
+        let password = password.map(|p| p.to_string());
+        let slots = vec![Arc::new(Mutex::new(Some(ConnState::Idle {
+            addr: addr.clone(),
+            password: password.clone(),
+        })))];
+        Self::from_slots(Some((addr, password)), slots)
+    }
+
+    /// Wraps a stream that is already connected and authenticated, for
+    /// callers that set up the connection themselves, e.g. over a tunnel.
+    ///
+    /// The transport cannot reconnect on its own: once the stream fails,
+    /// requests keep failing with that error.
+    pub fn from_stream<Io>(stream: DaemonStream<Io>) -> Self
+    where
+        Io: AsyncRead + AsyncWrite + Send + Unpin + 'static,
+    {
+        let stream = stream.map_io(|io| Box::new(io) as Box<dyn StreamIo>);
+        Self::from_slots(
+            None,
+            vec![Arc::new(Mutex::new(Some(ConnState::Ready(stream))))],
+        )
+    }
+
+    /// Creates a transport that spreads requests over `n` connections, so
+    /// clones of it can have up to `n` requests in flight at once.
+    ///
+    /// The daemon answers one request at a time per connection and only
+    /// accepts a handful of simultaneous GUI RPC connections, so keep `n`
+    /// small. Connections are opened lazily on first use; `n` of zero is
+    /// treated as one.
+    pub fn with_connections<A: Display, P: Display>(
+        addr: A,
+        password: Option<P>,
+        n: usize,
+    ) -> Self {
         let addr = addr.to_string();
         let password = password.map(|p| p.to_string());
+        let slots = (0..n.max(1))
+            .map(|_| {
+                Arc::new(Mutex::new(Some(ConnState::Idle {
+                    addr: addr.clone(),
+                    password: password.clone(),
+                })))
+            })
+            .collect::<Vec<_>>();
+        Self::from_slots(Some((addr, password)), slots)
+    }
+
+    /// Pings every open connection each `interval` with a cheap
+    /// `get_cc_status`, so the daemon does not drop it as idle. A connection
+    /// whose ping fails, or goes unanswered for `interval`, is reopened on
+    /// its next use instead of keeping the error.
+    ///
+    /// This spawns a background task on the current Tokio runtime, so it must
+    /// be called from within one. The task ends once the transport and all
+    /// its clones are dropped.
+    #[must_use]
+    pub fn with_keepalive(self, interval: Duration) -> Self {
+        let slots = Arc::downgrade(&self.slots);
+        let target = self.target.clone();
+        tokio::spawn(async move {
+            let mut ticks =
+                tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+            loop {
+                ticks.tick().await;
+                let Some(slots) = slots.upgrade() else {
+                    break;
+                };
+                for slot in slots.iter() {
+                    let mut state = slot.lock().await;
+                    let Some(ConnState::Ready(conn)) = &mut *state else {
+                        continue;
+                    };
+                    // The slot stays locked during the ping, so don't let a
+                    // daemon that stopped answering hold it forever.
+                    let ping = tokio::time::timeout(
+                        interval,
+                        conn.query(vec![treexml::Element::new("get_cc_status")]),
+                    )
+                    .await
+                    .unwrap_or_else(|_| Err(Error::Timeout("Keepalive ping timed out".into())));
+                    if let Err(e) = ping {
+                        *state = Some(target.clone().map_or(
+                            ConnState::Error(e),
+                            |(addr, password)| ConnState::Idle { addr, password },
+                        ));
+                    }
+                }
+            }
+        });
+        self
+    }
+
+    fn from_slots(target: Option<(String, Option<String>)>, slots: Vec<ConnSlot>) -> Self {
         Self {
-            state: Arc::new(Mutex::new(Some(ConnState::Connecting(Box::pin(
-                DaemonStream::connect(addr, password),
-            ))))),
+            target,
+            slots: slots.into(),
+            next_slot: Arc::new(AtomicUsize::new(0)),
+            slot: 0,
         }
     }
 }
@@ -94,67 +340,241 @@ impl Transport {
 impl tower::Service<Vec<treexml::Element>> for Transport {
     type Response = Vec<treexml::Element>;
     type Error = Error;
-    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>>>>;
-
-    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
-        let Ok(mut g) = self.state.try_lock() else {
-            return Poll::Pending;
-        };
-
-        let (state, out) = match g.take() {
-            Some(ConnState::Connecting(mut future)) => {
-                let res = future.as_mut().poll(cx);
-                match res {
-                    Poll::Pending => (Some(ConnState::Connecting(future)), Poll::Pending),
-                    Poll::Ready(Ok(conn)) => (Some(ConnState::Ready(conn)), Poll::Ready(Ok(()))),
-                    Poll::Ready(Err(e)) => (None, Poll::Ready(Err(e))),
-                }
-            }
-            Some(ConnState::Ready(conn)) => (Some(ConnState::Ready(conn)), Poll::Ready(Ok(()))),
-            Some(ConnState::Error(error)) => (
-                Some(ConnState::Error(error.clone())),
-                Poll::Ready(Err(error)),
-            ),
-            None => (
-                None,
-                Poll::Ready(Err(Error::Null("Null state".to_string()))),
-            ),
-        };
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
 
-        *g = state;
-        out
+    /// Picks the connection for the next `call`. Waiting for a busy
+    /// connection and (re)connecting happen in the response future, so
+    /// dropping that future, e.g. on a timeout, releases the connection.
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.slot = self.next_slot.fetch_add(1, Ordering::Relaxed) % self.slots.len();
+        Poll::Ready(Ok(()))
     }
 
     fn call(&mut self, req: Vec<treexml::Element>) -> Self::Future {
-        let state = self.state.clone();
+        let state = self.slots[self.slot].clone();
+        let target = self.target.clone();
         Box::pin(async move {
+            // The mutex queues waiters fairly and wakes them on release.
             let mut state = state.lock().await;
 
-            let Some(ConnState::Ready(mut conn)) = state.take() else {
-                unreachable!()
+            // The slot stays idle until the connect succeeds, so a failed or
+            // abandoned attempt is retried by the next request.
+            if let Some(ConnState::Idle { addr, password }) = &*state {
+                let conn = connect(addr.clone(), password.clone()).await?;
+                *state = Some(ConnState::Ready(conn));
+            }
+            let mut conn = match state.take() {
+                Some(ConnState::Ready(conn)) => conn,
+                Some(ConnState::Error(e)) => {
+                    *state = Some(ConnState::Error(e.clone()));
+                    return Err(e);
+                }
+                other => {
+                    *state = other;
+                    return Err(Error::Null("Transport is not ready".to_string()));
+                }
             };
 
             let query_res = conn.query(req).await;
 
-            if let Err(e) = &query_res {
-                *state = Some(ConnState::Error(e.clone()));
-            }
+            // A failed query leaves the stream in an unknown state, so the
+            // next request reopens it when there is a target to reopen.
+            *state = Some(match (&query_res, target) {
+                (Ok(_), _) => ConnState::Ready(conn),
+                (Err(_), Some((addr, password))) => ConnState::Idle { addr, password },
+                (Err(e), None) => ConnState::Error(e.clone()),
+            });
 
             query_res
         })
     }
 }
 
+/// The RPC surface of a BOINC client.
+///
+/// [`Client`] implements this for any compatible transport; downstream code
+/// can depend on `dyn BoincApi` or a generic parameter to inject fakes.
+/// Methods added since the trait was introduced default to
+/// [`Error::Unsupported`], so implementations only need the original set.
+///
+/// The methods are declared with `async-trait` rather than native `async fn`
+/// in traits: it keeps the trait usable as `dyn BoincApi`, at the cost of
+/// one boxed future per call.
+/// Futures are not required to be `Send`, so fakes may hold `Rc` or
+/// `RefCell` state; to spawn calls onto a multi-threaded runtime, call the
+/// inherent methods of a `Client<Transport>`, whose futures are `Send`.
+#[async_trait(?Send)]
+pub trait BoincApi {
+    async fn get_messages(&mut self, seqno: i64) -> Result<Vec<models::Message>, Error>;
+    async fn get_messages_since(
+        &mut self,
+        seqno: i64,
+        max: usize,
+    ) -> Result<Vec<models::Message>, Error> {
+        let mut messages = self.get_messages(seqno).await?;
+        messages.truncate(max);
+        Ok(messages)
+    }
+    async fn get_projects(&mut self) -> Result<Vec<models::ProjectInfo>, Error>;
+    async fn get_account_manager_info(&mut self) -> Result<models::AccountManagerInfo, Error>;
+    async fn get_account_manager_rpc_status(&mut self) -> Result<i32, Error>;
+    async fn connect_to_account_manager(
+        &mut self,
+        url: &str,
+        name: &str,
+        password: &str,
+        cookie: Option<&str>,
+        timeout: Duration,
+    ) -> Result<(), Error>;
+    async fn exchange_versions(
+        &mut self,
+        info: &models::VersionInfo,
+    ) -> Result<models::VersionInfo, Error>;
+    async fn get_results(&mut self, active_only: bool) -> Result<Vec<models::TaskResult>, Error>;
+    async fn set_mode(
+        &mut self,
+        c: models::Component,
+        m: models::RunMode,
+        duration: models::ModeDuration,
+    ) -> Result<(), Error>;
+    async fn get_host_info(&mut self) -> Result<models::HostInfo, Error>;
+    async fn set_language(&mut self, v: &str) -> Result<(), Error>;
+    // Methods below were added after the trait was introduced; their
+    // default bodies keep existing implementations compiling.
+    async fn get_daily_xfer_history(&mut self) -> Result<Vec<models::DailyTransfer>, Error> {
+        Err(unsupported("get_daily_xfer_history"))
+    }
+    async fn get_global_prefs_working(&mut self) -> Result<models::GlobalPreferences, Error> {
+        Err(unsupported("get_global_prefs_working"))
+    }
+    async fn get_state(&mut self) -> Result<models::ClientState, Error> {
+        Err(unsupported("get_state"))
+    }
+    async fn get_cc_status(&mut self) -> Result<models::CcStatus, Error> {
+        Err(unsupported("get_cc_status"))
+    }
+    async fn get_disk_usage(&mut self) -> Result<models::DiskUsage, Error> {
+        Err(unsupported("get_disk_usage"))
+    }
+    async fn get_file_transfers(&mut self) -> Result<Vec<models::FileTransfer>, Error> {
+        Err(unsupported("get_file_transfers"))
+    }
+    async fn get_proxy_settings(&mut self) -> Result<models::ProxyInfo, Error> {
+        Err(unsupported("get_proxy_settings"))
+    }
+    async fn result_op(
+        &mut self,
+        _op: models::ResultOp,
+        _project_url: &str,
+        _name: &str,
+    ) -> Result<(), Error> {
+        Err(unsupported("result_op"))
+    }
+    async fn get_project_status(&mut self) -> Result<Vec<models::Project>, Error> {
+        Err(unsupported("get_project_status"))
+    }
+    async fn project_op(
+        &mut self,
+        _op: models::ProjectOp,
+        _project_url: &str,
+    ) -> Result<(), Error> {
+        Err(unsupported("project_op"))
+    }
+}
+
+/// A BOINC client over the transport `S`.
+///
+/// Cloning a `Client<Transport>` is cheap: clones share the transport's
+/// connections, and requests from them are serialized per connection.
+#[derive(Clone)]
 pub struct Client<S> {
     transport: S,
+    /// Highest message sequence number returned by [`Client::get_new_messages`].
+    last_seqno: i64,
+    /// Daemon version from the last [`Client::exchange_versions`].
+    server_version: Option<models::VersionInfo>,
+    /// How long [`Client::get_projects`] reuses a fetched list, if at all.
+    projects_ttl: Option<Duration>,
+    /// The last fetched project list and when it was fetched.
+    projects_cache: Option<(tokio::time::Instant, Vec<models::ProjectInfo>)>,
 }
 
+#[allow(clippy::missing_errors_doc)]
 impl<S> Client<S>
 where
     S: tower::Service<Vec<treexml::Element>, Response = Vec<treexml::Element>, Error = Error>,
 {
     pub const fn new(transport: S) -> Self {
-        Self { transport }
+        Self {
+            transport,
+            last_seqno: 0,
+            server_version: None,
+            projects_ttl: None,
+            projects_cache: None,
+        }
+    }
+
+    /// Makes [`Self::get_projects`] reuse the list it fetched for `ttl`
+    /// instead of asking the daemon again.
+    ///
+    /// The list of all BOINC projects is large and rarely changes, so this
+    /// suits attach dialogs that query it repeatedly. Clones made afterwards
+    /// start with a copy of the cache and keep it separately.
+    #[must_use]
+    pub const fn with_projects_cache(mut self, ttl: Duration) -> Self {
+        self.projects_ttl = Some(ttl);
+        self
+    }
+
+    /// Drops the cached project list, so the next [`Self::get_projects`]
+    /// fetches it from the daemon.
+    pub fn invalidate_projects_cache(&mut self) {
+        self.projects_cache = None;
+    }
+
+    /// The daemon's version as reported by the last
+    /// [`Self::exchange_versions`], if it has been called.
+    pub const fn server_version(&self) -> Option<&models::VersionInfo> {
+        self.server_version.as_ref()
+    }
+
+    /// Fails with [`Error::Unsupported`] if the daemon is known to predate
+    /// `rpc`. Without a negotiated version the request is sent regardless.
+    fn require_version(
+        &self,
+        rpc: &str,
+        (major, minor, release): (i64, i64, i64),
+    ) -> Result<(), Error> {
+        match &self.server_version {
+            Some(v) if !v.at_least(major, minor, release) => Err(Error::Unsupported {
+                rpc: rpc.into(),
+                min_version: Some(format!("{major}.{minor}.{release}")),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Sends a request, retrying it once if the connection was lost and the
+    /// request [is idempotent](is_idempotent). [`Transport`] reopens a failed
+    /// connection on the next request, so the retry goes over a fresh one.
+    /// Other failures, such as an oversized or malformed reply, would only
+    /// fail again and are returned as they are.
+    async fn query(
+        &mut self,
+        req_data: Vec<treexml::Element>,
+    ) -> Result<Vec<treexml::Element>, Error> {
+        let retry = req_data
+            .first()
+            .is_some_and(|r| is_idempotent(&r.name))
+            .then(|| req_data.clone());
+        self.transport.ready().await?;
+        match (self.transport.call(req_data).await, retry) {
+            (Err(e), Some(req_data)) if is_connection_lost(&e) => {
+                self.transport.ready().await?;
+                self.transport.call(req_data).await
+            }
+            (res, _) => res,
+        }
     }
 
     async fn get_object<T: for<'a> From<&'a treexml::Element>>(
@@ -162,8 +582,9 @@ where
         req_data: Vec<treexml::Element>,
         object_tag: &str,
     ) -> Result<T, Error> {
-        self.transport.ready().await?;
-        let data = self.transport.call(req_data).await?;
+        let rpc = req_data.first().map(|r| r.name.clone()).unwrap_or_default();
+        let data = self.query(req_data).await?;
+        check_reply_not_empty(&rpc, &data)?;
         verify_rpc_reply_contents(&data)?;
         for child in &data {
             if child.name == object_tag {
@@ -188,19 +609,38 @@ where
         vec_tag: &str,
         object_tag: &str,
     ) -> Result<Vec<T>, Error> {
+        self.get_vec_limited(req_data, vec_tag, object_tag, usize::MAX)
+            .await
+            .map(|(v, _)| v)
+    }
+
+    async fn get_vec_limited<T: for<'a> From<&'a treexml::Element>>(
+        &mut self,
+        req_data: Vec<treexml::Element>,
+        vec_tag: &str,
+        object_tag: &str,
+        max: usize,
+    ) -> Result<(Vec<T>, bool), Error> {
+        let rpc = req_data.first().map(|r| r.name.clone()).unwrap_or_default();
         let mut v = Vec::new();
+        let mut truncated = false;
         {
-            self.transport.ready().await?;
-            let data = self.transport.call(req_data).await?;
+            let data = self.query(req_data).await?;
+            check_reply_not_empty(&rpc, &data)?;
             verify_rpc_reply_contents(&data)?;
             let mut success = false;
             for child in data {
                 if child.name == vec_tag {
                     success = true;
                     for vec_child in &child.children {
-                        if vec_child.name == object_tag {
-                            v.push(T::from(vec_child));
+                        if vec_child.name != object_tag {
+                            continue;
+                        }
+                        if v.len() >= max {
+                            truncated = true;
+                            break;
                         }
+                        v.push(T::from(vec_child));
                     }
                 }
             }
@@ -208,7 +648,30 @@ where
                 return Err(Error::DataParse("Objects not found.".to_string()));
             }
         }
-        Ok(v)
+        Ok((v, truncated))
+    }
+
+    async fn get_vec_with_raw<T: for<'a> From<&'a treexml::Element>>(
+        &mut self,
+        req_data: Vec<treexml::Element>,
+        vec_tag: &str,
+        object_tag: &str,
+    ) -> Result<(Vec<T>, String), Error> {
+        let rpc = req_data.first().map(|r| r.name.clone()).unwrap_or_default();
+        let data = self.query(req_data).await?;
+        check_reply_not_empty(&rpc, &data)?;
+        verify_rpc_reply_contents(&data)?;
+        let vec_node = data
+            .iter()
+            .find(|child| child.name == vec_tag)
+            .ok_or_else(|| Error::DataParse("Objects not found.".to_string()))?;
+        let v = vec_node
+            .children
+            .iter()
+            .filter(|vec_child| vec_child.name == object_tag)
+            .map(T::from)
+            .collect();
+        Ok((v, util::serialize_node(vec_node)))
     }
 
     async fn get_vec_by_req_tag<T: for<'a> From<&'a treexml::Element>>(
@@ -234,9 +697,101 @@ where
         .await
     }
 
+    /// Like [`Self::get_messages`], but decodes at most `max` messages.
+    ///
+    /// The daemon always sends everything after `seqno`, so the cut is made
+    /// client-side: the whole reply is still received and parsed, and only
+    /// the conversion of messages past the first `max` is skipped. Peak
+    /// memory is the same as for [`Self::get_messages`].
+    pub async fn get_messages_since(
+        &mut self,
+        seqno: i64,
+        max: usize,
+    ) -> Result<Vec<models::Message>, Error> {
+        self.get_vec_limited(
+            vec![{
+                let mut node = treexml::Element::new("get_messages");
+                node.text = Some(format!("{seqno}"));
+                node
+            }],
+            "msgs",
+            "msg",
+            max,
+        )
+        .await
+        .map(|(v, _)| v)
+    }
+
+    /// Fetches notices with a sequence number above `seqno`; `0` returns all
+    /// of them. Removed notices come back with [`models::Notice::is_removal`]
+    /// set.
+    pub async fn get_notices(&mut self, seqno: i64) -> Result<Vec<models::Notice>, Error> {
+        self.get_vec(
+            vec![{
+                let mut node = treexml::Element::new("get_notices");
+                let mut seqno_node = treexml::Element::new("seqno");
+                seqno_node.text = Some(format!("{seqno}"));
+                node.children.push(seqno_node);
+                node
+            }],
+            "notices",
+            "notice",
+        )
+        .await
+    }
+
+    /// Fetches the messages logged since the previous call, starting from the
+    /// beginning of the log on the first call.
+    pub async fn get_new_messages(&mut self) -> Result<Vec<models::Message>, Error> {
+        let messages = self.get_messages(self.last_seqno).await?;
+        if let Some(seqno) = messages.iter().filter_map(|m| m.msg_number).max() {
+            self.last_seqno = self.last_seqno.max(seqno);
+        }
+        Ok(messages)
+    }
+
+    /// Fetches messages after `seqno` and keeps only scheduler RPC events.
+    pub async fn get_scheduler_messages(
+        &mut self,
+        seqno: i64,
+    ) -> Result<Vec<models::Message>, Error> {
+        let mut messages = self.get_messages(seqno).await?;
+        messages.retain(models::Message::is_scheduler_event);
+        Ok(messages)
+    }
+
+    /// Fetches messages after `seqno` and keeps those from `project_name`,
+    /// compared case-insensitively. Messages from the client itself, which
+    /// carry no project, are kept when `include_core` is set.
+    pub async fn get_messages_for_project(
+        &mut self,
+        seqno: i64,
+        project_name: &str,
+        include_core: bool,
+    ) -> Result<Vec<models::Message>, Error> {
+        let mut messages = self.get_messages(seqno).await?;
+        messages.retain(|m| match m.project_name.as_deref() {
+            None | Some("") => include_core,
+            Some(name) => name.eq_ignore_ascii_case(project_name),
+        });
+        Ok(messages)
+    }
+
+    /// The list of all BOINC projects, which may be cached; see
+    /// [`Self::with_projects_cache`].
     pub async fn get_projects(&mut self) -> Result<Vec<models::ProjectInfo>, Error> {
-        self.get_vec_by_req_tag("get_all_projects_list", "projects", "project")
-            .await
+        if let (Some(ttl), Some((fetched, projects))) = (self.projects_ttl, &self.projects_cache) {
+            if fetched.elapsed() < ttl {
+                return Ok(projects.clone());
+            }
+        }
+        let projects: Vec<models::ProjectInfo> = self
+            .get_vec_by_req_tag("get_all_projects_list", "projects", "project")
+            .await?;
+        if self.projects_ttl.is_some() {
+            self.projects_cache = Some((tokio::time::Instant::now(), projects.clone()));
+        }
+        Ok(projects)
     }
 
     pub async fn get_account_manager_info(&mut self) -> Result<models::AccountManagerInfo, Error> {
@@ -245,10 +800,8 @@ where
     }
 
     pub async fn get_account_manager_rpc_status(&mut self) -> Result<i32, Error> {
-        self.transport.ready().await?;
         let data = self
-            .transport
-            .call(vec![treexml::Element::new("acct_mgr_rpc_poll")])
+            .query(vec![treexml::Element::new("acct_mgr_rpc_poll")])
             .await?;
         verify_rpc_reply_contents(&data)?;
 
@@ -265,11 +818,43 @@ where
         v.ok_or_else(|| Error::DataParse("acct_mgr_rpc_reply node not found".into()))
     }
 
+    /// Connects to an account manager and waits for the daemon to finish
+    /// contacting it, failing with [`Error::Timeout`] after `timeout`.
+    ///
+    /// The daemon accepts `acct_mgr_rpc` before it has talked to the account
+    /// manager, so the outcome is only known once polling completes. Rejected
+    /// credentials map to [`Error::InvalidPassword`] and an unusable URL to
+    /// [`Error::InvalidURL`]. See [`Self::attach_account_manager`] for when
+    /// `cookie` is needed.
     pub async fn connect_to_account_manager(
         &mut self,
         url: &str,
         name: &str,
         password: &str,
+        cookie: Option<&str>,
+        timeout: Duration,
+    ) -> Result<(), Error> {
+        match self
+            .attach_account_manager(url, name, password, cookie, timeout)
+            .await?
+        {
+            models::AmAttachOutcome::Success => Ok(()),
+            models::AmAttachOutcome::BadCredentials => Err(Error::InvalidPassword(format!(
+                "Account manager {url} rejected the credentials"
+            ))),
+            models::AmAttachOutcome::NetworkError => Err(Error::Network(format!(
+                "Could not reach account manager {url}"
+            ))),
+            models::AmAttachOutcome::Failed(error_num) => Err(Error::Status(error_num)),
+        }
+    }
+
+    async fn send_acct_mgr_rpc(
+        &mut self,
+        url: &str,
+        name: &str,
+        password: &str,
+        cookie: Option<&str>,
     ) -> Result<bool, Error> {
         let mut req_node = treexml::Element::new("acct_mgr_rpc");
         req_node.children = vec![
@@ -289,11 +874,62 @@ where
                 node
             },
         ];
+        if let Some(cookie) = cookie {
+            let mut node = treexml::Element::new("cookie");
+            node.text = Some(cookie.into());
+            req_node.children.push(node);
+        }
         self.transport.ready().await?;
         let root_node = self.transport.call(vec![req_node]).await?;
         verify_rpc_reply_contents(&root_node)
     }
 
+    /// Connects to an account manager and polls the RPC until the daemon
+    /// reports an outcome, failing with [`Error::Timeout`] after `timeout`.
+    ///
+    /// Managers whose [`models::AccountManagerInfo::cookie_required`] is set
+    /// expect the one-time cookie the user got from logging in on their web
+    /// site for the first attach. Pass it as `cookie`; if it is missing, send
+    /// the user to `cookie_failure_url`. Later attaches don't need it.
+    pub async fn attach_account_manager(
+        &mut self,
+        url: &str,
+        name: &str,
+        password: &str,
+        cookie: Option<&str>,
+        timeout: Duration,
+    ) -> Result<models::AmAttachOutcome, Error> {
+        const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+        self.send_acct_mgr_rpc(url, name, password, cookie).await?;
+        tokio::time::timeout(timeout, async {
+            loop {
+                let error_num = self.get_account_manager_rpc_status().await?;
+                if error_num != models::BoincErrorCode::InProgress.code() {
+                    return Ok(models::AmAttachOutcome::from(error_num));
+                }
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
+        })
+        .await
+        .map_err(|_| Error::Timeout(format!("Account manager {url} did not reply in time")))?
+    }
+
+    /// Like [`Self::connect_to_account_manager`], then returns the daemon's
+    /// account manager info so the caller can check the URL and name stuck.
+    pub async fn connect_and_verify_account_manager(
+        &mut self,
+        url: &str,
+        name: &str,
+        password: &str,
+        cookie: Option<&str>,
+        timeout: Duration,
+    ) -> Result<models::AccountManagerInfo, Error> {
+        self.connect_to_account_manager(url, name, password, cookie, timeout)
+            .await?;
+        self.get_account_manager_info().await
+    }
+
     pub async fn exchange_versions(
         &mut self,
         info: &models::VersionInfo,
@@ -301,12 +937,12 @@ where
         let mut content_node = treexml::Element::new("exchange_versions");
         {
             let mut node = treexml::Element::new("major");
-            node.text = info.minor.map(|v| format!("{v}"));
+            node.text = info.major.map(|v| format!("{v}"));
             content_node.children.push(node);
         }
         {
             let mut node = treexml::Element::new("minor");
-            node.text = info.major.map(|v| format!("{v}"));
+            node.text = info.minor.map(|v| format!("{v}"));
             content_node.children.push(node);
         }
         {
@@ -314,68 +950,377 @@ where
             node.text = info.release.map(|v| format!("{v}"));
             content_node.children.push(node);
         }
-        self.get_object(vec![content_node], "server_version").await
+        let version: models::VersionInfo = self
+            .get_object(vec![content_node], "server_version")
+            .await?;
+        self.server_version = Some(version.clone());
+        Ok(version)
+    }
+
+    /// The daemon's version as `major.minor.release`, e.g. "7.16.16".
+    ///
+    /// Sends this crate's default [`models::VersionInfo`] as our own version.
+    /// Missing minor or release numbers are shown as 0.
+    pub async fn get_client_version(&mut self) -> Result<String, Error> {
+        let version = self
+            .exchange_versions(&models::VersionInfo::default())
+            .await?;
+        let major = version
+            .major
+            .ok_or_else(|| Error::DataParse("server_version has no major version".into()))?;
+        Ok(format!(
+            "{major}.{}.{}",
+            version.minor.unwrap_or(0),
+            version.release.unwrap_or(0)
+        ))
     }
 
     pub async fn get_results(
         &mut self,
         active_only: bool,
     ) -> Result<Vec<models::TaskResult>, Error> {
-        self.get_vec(
-            vec![{
-                let mut node = treexml::Element::new("get_results");
-                if active_only {
-                    let mut ao_node = treexml::Element::new("active_only");
-                    ao_node.text = Some("1".into());
-                    node.children.push(ao_node);
-                }
-                node
-            }],
-            "results",
-            "result",
-        )
-        .await
+        self.get_vec(vec![results_request(active_only)], "results", "result")
+            .await
+    }
+
+    /// Like [`Self::get_results`], but returns at most `max` results, for when
+    /// a preview is enough. The flag is set when the reply held more.
+    ///
+    /// The whole reply is still received and parsed; only the conversion of
+    /// results past the first `max` is skipped.
+    pub async fn get_results_limited(
+        &mut self,
+        active_only: bool,
+        max: usize,
+    ) -> Result<(Vec<models::TaskResult>, bool), Error> {
+        self.get_vec_limited(vec![results_request(active_only)], "results", "result", max)
+            .await
+    }
+
+    /// Like [`Self::get_results`], but also returns the `<results>` node of
+    /// the reply as XML text, e.g. for logging.
+    pub async fn get_results_raw(
+        &mut self,
+        active_only: bool,
+    ) -> Result<(Vec<models::TaskResult>, String), Error> {
+        self.get_vec_with_raw(vec![results_request(active_only)], "results", "result")
+            .await
+    }
+
+    /// Results whose report deadline is before Unix time `deadline_epoch`,
+    /// soonest first. Results without a deadline are left out.
+    pub async fn get_results_due_before(
+        &mut self,
+        deadline_epoch: f64,
+    ) -> Result<Vec<models::TaskResult>, Error> {
+        let mut results = self
+            .get_results(false)
+            .await?
+            .into_iter()
+            .filter(|r| r.report_deadline.is_some_and(|d| d < deadline_epoch))
+            .collect::<Vec<_>>();
+        results.sort_by(|a, b| {
+            a.report_deadline
+                .unwrap_or_default()
+                .total_cmp(&b.report_deadline.unwrap_or_default())
+        });
+        Ok(results)
+    }
+
+    /// Results passing `filter`, e.g. only those that failed to compute or
+    /// upload.
+    pub async fn get_results_filtered(
+        &mut self,
+        filter: &models::GetResultsFilter,
+    ) -> Result<Vec<models::TaskResult>, Error> {
+        let mut results = self.get_results(filter.active_only).await?;
+        results.retain(|r| filter.matches(r));
+        Ok(results)
+    }
+
+    /// How many results are in each stage, from a single `get_results` call.
+    pub async fn get_result_counts(&mut self) -> Result<models::ResultCounts, Error> {
+        Ok(models::ResultCounts::from_results(
+            &self.get_results(false).await?,
+        ))
+    }
+
+    /// All results keyed by workunit name, for projects that send several
+    /// results per workunit. Results without a `wu_name` are keyed by "".
+    pub async fn get_results_grouped_by_wu(
+        &mut self,
+    ) -> Result<HashMap<String, Vec<models::TaskResult>>, Error> {
+        let mut groups = HashMap::<_, Vec<_>>::new();
+        for result in self.get_results(false).await? {
+            groups
+                .entry(result.wu_name.clone().unwrap_or_default())
+                .or_default()
+                .push(result);
+        }
+        Ok(groups)
     }
 
+    /// Sets the run mode of a component, permanently or for a while.
+    ///
+    /// [`models::Component::All`] sets CPU, GPU and network modes in turn, as
+    /// the daemon handles a single command per request. If the daemon rejects
+    /// one after others were applied, this fails with
+    /// [`Error::PartiallyApplied`] listing the components that took effect.
+    ///
+    /// [`models::Component::GPU`] applies to every GPU: `set_gpu_mode` has no
+    /// device selector in any client version. To keep work off a single
+    /// card, exclude it with `<exclude_gpu>` in the client's `cc_config.xml`.
     pub async fn set_mode(
         &mut self,
         c: models::Component,
         m: models::RunMode,
-        duration: f64,
+        duration: models::ModeDuration,
     ) -> Result<(), Error> {
-        self.transport.ready().await?;
-        let rsp_root = self
-            .transport
-            .call(vec![{
-                let comp_desc = match c {
-                    models::Component::CPU => "run",
-                    models::Component::GPU => "gpu",
-                    models::Component::Network => "network",
-                }
-                .to_string();
-                let mode_desc = match m {
-                    models::RunMode::Always => "always",
-                    models::RunMode::Auto => "auto",
-                    models::RunMode::Never => "never",
-                    models::RunMode::Restore => "restore",
-                }
-                .to_string();
+        let components: &[models::Component] = match c {
+            models::Component::All => &[
+                models::Component::CPU,
+                models::Component::GPU,
+                models::Component::Network,
+            ],
+            _ => &[c],
+        };
+        let mode_desc = match m {
+            models::RunMode::Always => "always",
+            models::RunMode::Auto => "auto",
+            models::RunMode::Never => "never",
+            models::RunMode::Restore => "restore",
+        };
 
-                let mut node = treexml::Element::new(format!("set_{}_mode", &comp_desc));
+        let mut applied = Vec::new();
+        for &component in components {
+            let comp_desc = match component {
+                models::Component::CPU | models::Component::All => "run",
+                models::Component::GPU => "gpu",
+                models::Component::Network => "network",
+            };
+            let req = vec![{
+                let mut node = treexml::Element::new(format!("set_{comp_desc}_mode"));
                 let mut dur_node = treexml::Element::new("duration");
-                dur_node.text = Some(format!("{duration}"));
+                dur_node.text = Some(format!("{}", duration.as_secs_f64()));
                 node.children.push(dur_node);
                 node.children.push(treexml::Element::new(mode_desc));
                 node
+            }];
+            let res = async {
+                self.transport.ready().await?;
+                verify_rpc_reply_contents(&self.transport.call(req).await?)
+            }
+            .await;
+            match res {
+                Ok(_) => applied.push(component),
+                Err(error) if applied.is_empty() => return Err(error),
+                Err(error) => {
+                    return Err(Error::PartiallyApplied {
+                        applied,
+                        error: Box::new(error),
+                    })
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Suspends, resumes or aborts a task; see [`models::ResultOp`] for what
+    /// an abort does to a running task.
+    pub async fn result_op(
+        &mut self,
+        op: models::ResultOp,
+        project_url: &str,
+        name: &str,
+    ) -> Result<(), Error> {
+        self.transport.ready().await?;
+        let rsp_root = self
+            .transport
+            .call(vec![{
+                let mut node = treexml::Element::new(match op {
+                    models::ResultOp::Suspend => "suspend_result",
+                    models::ResultOp::Resume => "resume_result",
+                    models::ResultOp::Abort => "abort_result",
+                });
+                let mut url_node = treexml::Element::new("project_url");
+                url_node.text = Some(util::canonicalize_master_url(project_url));
+                node.children.push(url_node);
+                let mut name_node = treexml::Element::new("name");
+                name_node.text = Some(name.into());
+                node.children.push(name_node);
+                node
             }])
             .await?;
         verify_rpc_reply_contents(&rsp_root)?;
         Ok(())
     }
 
-    pub async fn get_host_info(&mut self) -> Result<models::HostInfo, Error> {
-        self.get_object_by_req_tag("get_host_info", "host_info")
-            .await
+    /// Aborts a task and polls [`Self::get_results`] until the daemon reports
+    /// it as aborted or drops it, failing with [`Error::Timeout`] after `timeout`.
+    pub async fn abort_result_and_wait(
+        &mut self,
+        project_url: &str,
+        name: &str,
+        timeout: Duration,
+    ) -> Result<(), Error> {
+        const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+        self.result_op(models::ResultOp::Abort, project_url, name)
+            .await?;
+        tokio::time::timeout(timeout, async {
+            loop {
+                let results = self.get_results(false).await?;
+                let aborted = results
+                    .iter()
+                    .find(|r| r.name.as_deref() == Some(name))
+                    .is_none_or(|r| r.result_state() == Some(models::ResultState::Aborted));
+                if aborted {
+                    return Ok(());
+                }
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
+        })
+        .await
+        .map_err(|_| Error::Timeout(format!("Task {name} was not aborted in time")))?
+    }
+
+    /// The projects the client is attached to.
+    pub async fn get_project_status(&mut self) -> Result<Vec<models::Project>, Error> {
+        self.get_vec_by_req_tag("get_project_status", "projects", "project")
+            .await
+    }
+
+    pub async fn project_op(
+        &mut self,
+        op: models::ProjectOp,
+        project_url: &str,
+    ) -> Result<(), Error> {
+        self.transport.ready().await?;
+        let rsp_root = self
+            .transport
+            .call(vec![{
+                let mut node = treexml::Element::new(match op {
+                    models::ProjectOp::Reset => "project_reset",
+                    models::ProjectOp::Detach => "project_detach",
+                    models::ProjectOp::Update => "project_update",
+                    models::ProjectOp::Suspend => "project_suspend",
+                    models::ProjectOp::Resume => "project_resume",
+                    models::ProjectOp::NoMoreWork => "project_nomorework",
+                    models::ProjectOp::AllowMoreWork => "project_allowmorework",
+                    models::ProjectOp::DetachWhenDone => "project_detach_when_done",
+                    models::ProjectOp::DontDetachWhenDone => "project_dont_detach_when_done",
+                });
+                let mut url_node = treexml::Element::new("project_url");
+                url_node.text = Some(util::canonicalize_master_url(project_url));
+                node.children.push(url_node);
+                node
+            }])
+            .await?;
+        verify_rpc_reply_contents(&rsp_root)?;
+        Ok(())
+    }
+
+    /// Asks the client to detach from a project once its current tasks are
+    /// finished and reported. Returns as soon as the request is accepted.
+    pub async fn detach_when_done(&mut self, project_url: &str) -> Result<(), Error> {
+        self.project_op(models::ProjectOp::DetachWhenDone, project_url)
+            .await
+    }
+
+    /// Like [`Self::detach_when_done`], then polls [`Self::get_project_status`]
+    /// until the project is gone, failing with [`Error::Timeout`] after `timeout`.
+    ///
+    /// Detaching waits for running tasks to finish, so `timeout` may need to
+    /// be hours rather than seconds.
+    pub async fn detach_when_done_and_wait(
+        &mut self,
+        project_url: &str,
+        timeout: Duration,
+    ) -> Result<(), Error> {
+        const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+        self.detach_when_done(project_url).await?;
+        let project_url = util::canonicalize_master_url(project_url);
+        tokio::time::timeout(timeout, async {
+            loop {
+                let projects = self.get_project_status().await?;
+                if !projects.iter().any(|p| {
+                    p.master_url
+                        .as_deref()
+                        .is_some_and(|url| util::same_master_url(url, &project_url))
+                }) {
+                    return Ok(());
+                }
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
+        })
+        .await
+        .map_err(|_| Error::Timeout(format!("Project {project_url} was not detached in time")))?
+    }
+
+    /// Suspends CPU computation for `duration`, then reverts to the previous mode.
+    pub async fn snooze_cpu(&mut self, duration: Duration) -> Result<(), Error> {
+        self.set_mode(
+            models::Component::CPU,
+            models::RunMode::Never,
+            models::ModeDuration::For(duration),
+        )
+        .await
+    }
+
+    /// Suspends GPU computation for `duration`, then reverts to the previous mode.
+    pub async fn snooze_gpu(&mut self, duration: Duration) -> Result<(), Error> {
+        self.set_mode(
+            models::Component::GPU,
+            models::RunMode::Never,
+            models::ModeDuration::For(duration),
+        )
+        .await
+    }
+
+    /// Suspends network activity for `duration`, then reverts to the previous mode.
+    pub async fn snooze_network(&mut self, duration: Duration) -> Result<(), Error> {
+        self.set_mode(
+            models::Component::Network,
+            models::RunMode::Never,
+            models::ModeDuration::For(duration),
+        )
+        .await
+    }
+
+    /// Cancels a CPU snooze, restoring the previous mode immediately.
+    pub async fn unsnooze_cpu(&mut self) -> Result<(), Error> {
+        self.set_mode(
+            models::Component::CPU,
+            models::RunMode::Restore,
+            models::ModeDuration::Permanent,
+        )
+        .await
+    }
+
+    /// Cancels a GPU snooze, restoring the previous mode immediately.
+    pub async fn unsnooze_gpu(&mut self) -> Result<(), Error> {
+        self.set_mode(
+            models::Component::GPU,
+            models::RunMode::Restore,
+            models::ModeDuration::Permanent,
+        )
+        .await
+    }
+
+    /// Cancels a network snooze, restoring the previous mode immediately.
+    pub async fn unsnooze_network(&mut self) -> Result<(), Error> {
+        self.set_mode(
+            models::Component::Network,
+            models::RunMode::Restore,
+            models::ModeDuration::Permanent,
+        )
+        .await
+    }
+
+    pub async fn get_host_info(&mut self) -> Result<models::HostInfo, Error> {
+        self.get_object_by_req_tag("get_host_info", "host_info")
+            .await
     }
 
     pub async fn set_language(&mut self, v: &str) -> Result<(), Error> {
@@ -394,11 +1339,324 @@ where
         )?;
         Ok(())
     }
+
+    /// Writes the local preference override file, which takes precedence
+    /// over the preferences set on the project website.
+    ///
+    /// The client only picks the new file up after
+    /// [`Self::read_global_prefs_override`]; pass `apply` to call it as well.
+    /// `prefs` is checked with [`models::GlobalPreferences::validate`] first.
+    pub async fn set_global_prefs_override(
+        &mut self,
+        prefs: &models::GlobalPrefsOverride,
+        apply: bool,
+    ) -> Result<(), Error> {
+        prefs.validate()?;
+        self.transport.ready().await?;
+        verify_rpc_reply_contents(
+            &self
+                .transport
+                .call(vec![{
+                    let mut node = treexml::Element::new("set_global_prefs_override");
+                    node.children.push(prefs.into());
+                    node
+                }])
+                .await?,
+        )?;
+        if apply {
+            self.read_global_prefs_override().await?;
+        }
+        Ok(())
+    }
+
+    /// Drops the local preference override so the client goes back to the
+    /// preferences set on the project website, and applies the change.
+    ///
+    /// Only the local override file is affected; the web preferences
+    /// themselves are left alone.
+    pub async fn clear_global_prefs_override(&mut self) -> Result<(), Error> {
+        self.set_global_prefs_override(&models::GlobalPrefsOverride::default(), true)
+            .await
+    }
+
+    /// Makes the client re-read its local preference override file.
+    pub async fn read_global_prefs_override(&mut self) -> Result<(), Error> {
+        self.transport.ready().await?;
+        verify_rpc_reply_contents(
+            &self
+                .transport
+                .call(vec![treexml::Element::new("read_global_prefs_override")])
+                .await?,
+        )?;
+        Ok(())
+    }
+
+    /// Requires client 7.0 or later; see [`Self::server_version`].
+    pub async fn get_daily_xfer_history(&mut self) -> Result<Vec<models::DailyTransfer>, Error> {
+        self.require_version("get_daily_xfer_history", (7, 0, 0))?;
+        self.get_vec_by_req_tag("get_daily_xfer_history", "daily_xfers", "dx")
+            .await
+    }
+
+    pub async fn get_global_prefs_working(&mut self) -> Result<models::GlobalPreferences, Error> {
+        self.get_object_by_req_tag("get_global_prefs_working", "global_preferences")
+            .await
+    }
+
+    /// The client's full state. Over a [`Transport`], only the parts listed
+    /// in [`rpc::GET_STATE_SECTIONS`] are decoded.
+    pub async fn get_state(&mut self) -> Result<models::ClientState, Error> {
+        self.get_object_by_req_tag("get_state", "client_state")
+            .await
+    }
+
+    pub async fn get_cc_status(&mut self) -> Result<models::CcStatus, Error> {
+        self.get_object_by_req_tag("get_cc_status", "cc_status")
+            .await
+    }
+
+    pub async fn get_disk_usage(&mut self) -> Result<models::DiskUsage, Error> {
+        self.get_object_by_req_tag("get_disk_usage", "disk_usage_summary")
+            .await
+    }
+
+    /// Fetches the client status, active results and disk usage in turn.
+    ///
+    /// The daemon answers a single command per request, so this is three
+    /// round trips; they go out back to back on the same connection.
+    pub async fn snapshot(&mut self) -> Result<models::Snapshot, Error> {
+        Ok(models::Snapshot {
+            cc_status: self.get_cc_status().await?,
+            results: self.get_results(true).await?,
+            disk_usage: self.get_disk_usage().await?,
+        })
+    }
+
+    pub async fn get_file_transfers(&mut self) -> Result<Vec<models::FileTransfer>, Error> {
+        self.get_vec_by_req_tag("get_file_transfers", "file_transfers", "file_transfer")
+            .await
+    }
+
+    pub async fn get_proxy_settings(&mut self) -> Result<models::ProxyInfo, Error> {
+        self.get_object_by_req_tag("get_proxy_settings", "proxy_info")
+            .await
+    }
+
+    /// Whether the daemon has hit its daily transfer limit, as configured in
+    /// the working global preferences.
+    pub async fn is_over_bandwidth_limit(&mut self) -> Result<bool, Error> {
+        let prefs = self.get_global_prefs_working().await?;
+        let history = self.get_daily_xfer_history().await?;
+        Ok(prefs.exceeds_xfer_limit(&history, util::current_day()))
+    }
+}
+
+#[async_trait(?Send)]
+impl<S> BoincApi for Client<S>
+where
+    S: tower::Service<Vec<treexml::Element>, Response = Vec<treexml::Element>, Error = Error>,
+{
+    async fn get_messages(&mut self, seqno: i64) -> Result<Vec<models::Message>, Error> {
+        Self::get_messages(self, seqno).await
+    }
+
+    async fn get_messages_since(
+        &mut self,
+        seqno: i64,
+        max: usize,
+    ) -> Result<Vec<models::Message>, Error> {
+        Self::get_messages_since(self, seqno, max).await
+    }
+
+    async fn get_projects(&mut self) -> Result<Vec<models::ProjectInfo>, Error> {
+        Self::get_projects(self).await
+    }
+
+    async fn get_account_manager_info(&mut self) -> Result<models::AccountManagerInfo, Error> {
+        Self::get_account_manager_info(self).await
+    }
+
+    async fn get_account_manager_rpc_status(&mut self) -> Result<i32, Error> {
+        Self::get_account_manager_rpc_status(self).await
+    }
+
+    async fn connect_to_account_manager(
+        &mut self,
+        url: &str,
+        name: &str,
+        password: &str,
+        cookie: Option<&str>,
+        timeout: Duration,
+    ) -> Result<(), Error> {
+        Self::connect_to_account_manager(self, url, name, password, cookie, timeout).await
+    }
+
+    async fn exchange_versions(
+        &mut self,
+        info: &models::VersionInfo,
+    ) -> Result<models::VersionInfo, Error> {
+        Self::exchange_versions(self, info).await
+    }
+
+    async fn get_results(&mut self, active_only: bool) -> Result<Vec<models::TaskResult>, Error> {
+        Self::get_results(self, active_only).await
+    }
+
+    async fn set_mode(
+        &mut self,
+        c: models::Component,
+        m: models::RunMode,
+        duration: models::ModeDuration,
+    ) -> Result<(), Error> {
+        Self::set_mode(self, c, m, duration).await
+    }
+
+    async fn get_host_info(&mut self) -> Result<models::HostInfo, Error> {
+        Self::get_host_info(self).await
+    }
+
+    async fn set_language(&mut self, v: &str) -> Result<(), Error> {
+        Self::set_language(self, v).await
+    }
+
+    async fn get_daily_xfer_history(&mut self) -> Result<Vec<models::DailyTransfer>, Error> {
+        Self::get_daily_xfer_history(self).await
+    }
+
+    async fn get_global_prefs_working(&mut self) -> Result<models::GlobalPreferences, Error> {
+        Self::get_global_prefs_working(self).await
+    }
+
+    async fn get_state(&mut self) -> Result<models::ClientState, Error> {
+        Self::get_state(self).await
+    }
+
+    async fn get_cc_status(&mut self) -> Result<models::CcStatus, Error> {
+        Self::get_cc_status(self).await
+    }
+
+    async fn get_disk_usage(&mut self) -> Result<models::DiskUsage, Error> {
+        Self::get_disk_usage(self).await
+    }
+
+    async fn get_file_transfers(&mut self) -> Result<Vec<models::FileTransfer>, Error> {
+        Self::get_file_transfers(self).await
+    }
+
+    async fn get_proxy_settings(&mut self) -> Result<models::ProxyInfo, Error> {
+        Self::get_proxy_settings(self).await
+    }
+
+    async fn result_op(
+        &mut self,
+        op: models::ResultOp,
+        project_url: &str,
+        name: &str,
+    ) -> Result<(), Error> {
+        Self::result_op(self, op, project_url, name).await
+    }
+
+    async fn get_project_status(&mut self) -> Result<Vec<models::Project>, Error> {
+        Self::get_project_status(self).await
+    }
+
+    async fn project_op(&mut self, op: models::ProjectOp, project_url: &str) -> Result<(), Error> {
+        Self::project_op(self, op, project_url).await
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::errors::Error;
+    use super::{
+        errors::Error,
+        models,
+        rpc::{BoincCodec, CodecMode},
+        util, BoincApi, Client, ConnState, DaemonStream, Transport,
+    };
+    use async_trait::async_trait;
+    use futures::{SinkExt, TryStreamExt};
+    use std::future::Future;
+    use std::{
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+    use tokio_util::codec::Decoder;
+
+    /// Listens on a local port and serves a daemon that authorizes without a
+    /// password and answers every request with `<success/>`.
+    async fn fake_daemon() -> (std::net::SocketAddr, Arc<AtomicUsize>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let connections = Arc::new(AtomicUsize::new(0));
+        let accepted = connections.clone();
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                accepted.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(async move {
+                    let mut conn = BoincCodec::new(CodecMode::Server).framed(socket);
+                    while let Ok(Some(req)) = conn.try_next().await {
+                        let reply = match &*req[0].name {
+                            "auth1" => "authorized",
+                            _ => "success",
+                        };
+                        if conn.send(vec![treexml::Element::new(reply)]).await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+        (addr, connections)
+    }
+
+    /// A client that answers every request with `<success/>` and records the
+    /// requests it was sent.
+    fn recording_client() -> (
+        Client<
+            impl tower::Service<Vec<treexml::Element>, Response = Vec<treexml::Element>, Error = Error>,
+        >,
+        Arc<std::sync::Mutex<Vec<treexml::Element>>>,
+    ) {
+        let requests = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let client = Client::new(tower::service_fn({
+            let requests = requests.clone();
+            move |req: Vec<treexml::Element>| {
+                requests.lock().unwrap().extend(req);
+                async move { Ok::<_, Error>(vec![treexml::Element::new("success")]) }
+            }
+        }));
+        (client, requests)
+    }
+
+    /// A client whose transport answers each request tag with a canned reply.
+    fn mock_client(
+        replies: &[(&str, &str)],
+    ) -> Client<
+        impl tower::Service<Vec<treexml::Element>, Response = Vec<treexml::Element>, Error = Error>
+            + Clone,
+    > {
+        let replies = replies
+            .iter()
+            .map(|(req_tag, reply)| {
+                let root = util::parse_node(&format!(
+                    "<boinc_gui_rpc_reply>{reply}</boinc_gui_rpc_reply>"
+                ))
+                .unwrap();
+                ((*req_tag).to_string(), root.children)
+            })
+            .collect::<Vec<_>>();
+        Client::new(tower::service_fn(move |req: Vec<treexml::Element>| {
+            let reply = replies
+                .iter()
+                .find(|(req_tag, _)| req.first().is_some_and(|n| &n.name == req_tag))
+                .map(|(_, reply)| reply.clone())
+                .ok_or_else(|| Error::Daemon("Unexpected request".into()));
+            async move { reply }
+        }))
+    }
 
     #[test]
     fn verify_rpc_reply_contents() {
@@ -410,4 +1668,1557 @@ mod tests {
             Error::Auth("Missing authenticator".to_string())
         );
     }
+
+    #[test]
+    fn verify_rpc_reply_cdata_error() {
+        let reply = util::parse_node(
+            "<boinc_gui_rpc_reply>\
+             <error><![CDATA[Already attached to project]]></error>\
+             </boinc_gui_rpc_reply>",
+        )
+        .unwrap();
+        assert_eq!(
+            super::verify_rpc_reply_contents(&reply.children),
+            Err(Error::AlreadyAttached("Already attached to project".into()))
+        );
+    }
+
+    #[test]
+    fn verify_rpc_reply_error_num() {
+        let reply = util::parse_node(
+            "<boinc_gui_rpc_reply>\
+             <error><error_num>-136</error_num></error>\
+             </boinc_gui_rpc_reply>",
+        )
+        .unwrap();
+        assert_eq!(
+            super::verify_rpc_reply_contents(&reply.children),
+            Err(Error::Status(-136))
+        );
+    }
+
+    #[tokio::test]
+    async fn unsupported_rpc() {
+        let mut client = mock_client(&[
+            (
+                "get_disk_usage",
+                "<error>unrecognized op: get_disk_usage</error>",
+            ),
+            ("get_cc_status", ""),
+        ]);
+        assert_eq!(
+            client.get_disk_usage().await,
+            Err(Error::Unsupported {
+                rpc: "get_disk_usage".into(),
+                min_version: None,
+            })
+        );
+        assert_eq!(
+            client.get_cc_status().await,
+            Err(Error::Unsupported {
+                rpc: "get_cc_status".into(),
+                min_version: None,
+            })
+        );
+    }
+
+    #[test]
+    fn verify_rpc_reply_error_msg() {
+        let reply = util::parse_node(
+            "<boinc_gui_rpc_reply>\
+             <success/>\
+             <error_msg>Couldn't write state file</error_msg>\
+             </boinc_gui_rpc_reply>",
+        )
+        .unwrap();
+        assert_eq!(
+            super::verify_rpc_reply_contents(&reply.children),
+            Err(Error::Daemon("Couldn't write state file".into()))
+        );
+
+        let reply = util::parse_node(
+            "<boinc_gui_rpc_reply>\
+             <acct_mgr_rpc_reply><error_msg>Account manager is down</error_msg></acct_mgr_rpc_reply>\
+             </boinc_gui_rpc_reply>",
+        )
+        .unwrap();
+        assert_eq!(
+            super::verify_rpc_reply_contents(&reply.children),
+            Err(Error::Daemon("Account manager is down".into()))
+        );
+
+        let reply = util::parse_node(
+            "<boinc_gui_rpc_reply>\
+             <acct_mgr_rpc_reply><error_num>-206</error_num><error_msg>Invalid password</error_msg></acct_mgr_rpc_reply>\
+             </boinc_gui_rpc_reply>",
+        )
+        .unwrap();
+        assert_eq!(super::verify_rpc_reply_contents(&reply.children), Ok(false));
+    }
+
+    #[derive(Default)]
+    struct FakeClient {
+        language: Option<String>,
+    }
+
+    #[async_trait(?Send)]
+    impl BoincApi for FakeClient {
+        async fn get_messages(&mut self, _seqno: i64) -> Result<Vec<models::Message>, Error> {
+            Ok(vec![models::Message {
+                body: Some("Starting BOINC client".into()),
+                ..Default::default()
+            }])
+        }
+
+        async fn get_projects(&mut self) -> Result<Vec<models::ProjectInfo>, Error> {
+            Ok(vec![])
+        }
+
+        async fn get_account_manager_info(&mut self) -> Result<models::AccountManagerInfo, Error> {
+            Ok(models::AccountManagerInfo::default())
+        }
+
+        async fn get_account_manager_rpc_status(&mut self) -> Result<i32, Error> {
+            Ok(0)
+        }
+
+        async fn connect_to_account_manager(
+            &mut self,
+            _url: &str,
+            _name: &str,
+            _password: &str,
+            _cookie: Option<&str>,
+            _timeout: Duration,
+        ) -> Result<(), Error> {
+            Err(Error::Auth("unauthorized".into()))
+        }
+
+        async fn exchange_versions(
+            &mut self,
+            info: &models::VersionInfo,
+        ) -> Result<models::VersionInfo, Error> {
+            Ok(info.clone())
+        }
+
+        async fn get_results(
+            &mut self,
+            _active_only: bool,
+        ) -> Result<Vec<models::TaskResult>, Error> {
+            Ok(vec![])
+        }
+
+        async fn set_mode(
+            &mut self,
+            _c: models::Component,
+            _m: models::RunMode,
+            _duration: models::ModeDuration,
+        ) -> Result<(), Error> {
+            Ok(())
+        }
+
+        async fn get_host_info(&mut self) -> Result<models::HostInfo, Error> {
+            Ok(models::HostInfo::default())
+        }
+
+        async fn set_language(&mut self, v: &str) -> Result<(), Error> {
+            self.language = Some(v.into());
+            Ok(())
+        }
+    }
+
+    #[allow(clippy::future_not_send)]
+    async fn first_message_body(api: &mut dyn BoincApi) -> Option<String> {
+        api.get_messages(0).await.ok()?.pop()?.body
+    }
+
+    #[allow(clippy::future_not_send)]
+    async fn active_task_count(rpc: &mut impl BoincApi) -> usize {
+        rpc.get_results(true).await.map_or(0, |r| r.len())
+    }
+
+    #[tokio::test]
+    async fn boinc_api_fake() {
+        let mut fake = FakeClient::default();
+        assert_eq!(
+            first_message_body(&mut fake).await.as_deref(),
+            Some("Starting BOINC client")
+        );
+        assert_eq!(active_task_count(&mut fake).await, 0);
+        fake.set_language("en_US").await.unwrap();
+        assert_eq!(fake.language.as_deref(), Some("en_US"));
+        assert_eq!(
+            fake.connect_to_account_manager("", "", "", None, Duration::ZERO)
+                .await,
+            Err(Error::Auth("unauthorized".into()))
+        );
+        assert_eq!(
+            fake.get_cc_status().await,
+            Err(Error::Unsupported {
+                rpc: "get_cc_status".into(),
+                min_version: None,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn get_messages_since_limits_decoding() {
+        let mut client = mock_client(&[(
+            "get_messages",
+            "<msgs>\
+             <msg><seqno>1</seqno></msg>\
+             <msg><seqno>2</seqno></msg>\
+             <msg><seqno>3</seqno></msg>\
+             </msgs>",
+        )]);
+        let messages = client.get_messages_since(0, 2).await.unwrap();
+        assert_eq!(
+            messages.iter().map(|m| m.msg_number).collect::<Vec<_>>(),
+            vec![Some(1), Some(2)]
+        );
+    }
+
+    #[tokio::test]
+    async fn is_over_bandwidth_limit() {
+        let today = util::current_day();
+        let mut client = mock_client(&[
+            (
+                "get_global_prefs_working",
+                "<global_preferences>\
+                 <daily_xfer_limit_mb>10</daily_xfer_limit_mb>\
+                 <daily_xfer_period_days>2</daily_xfer_period_days>\
+                 </global_preferences>",
+            ),
+            (
+                "get_daily_xfer_history",
+                &format!(
+                    "<daily_xfers>\
+                     <dx><when>{}</when><up>0</up><down>100000000</down></dx>\
+                     <dx><when>{}</when><up>6000000</up><down>0</down></dx>\
+                     <dx><when>{today}</when><up>0</up><down>5000000</down></dx>\
+                     </daily_xfers>",
+                    today - 2,
+                    today - 1,
+                ),
+            ),
+        ]);
+        assert!(client.is_over_bandwidth_limit().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn get_scheduler_messages() {
+        let mut client = mock_client(&[(
+            "get_messages",
+            "<msgs>\
+             <msg><project></project><seqno>1</seqno>\
+             <body><![CDATA[Starting BOINC client version 8.0.2]]></body></msg>\
+             <msg><project>Einstein@Home</project><seqno>2</seqno>\
+             <body><![CDATA[Sending scheduler request: To fetch work.]]></body></msg>\
+             <msg><project>Einstein@Home</project><seqno>3</seqno>\
+             <body><![CDATA[Starting task h1_0001]]></body></msg>\
+             <msg><project>Einstein@Home</project><seqno>4</seqno>\
+             <body><![CDATA[Scheduler request completed: got 2 new tasks]]></body></msg>\
+             </msgs>",
+        )]);
+        let messages = client.get_scheduler_messages(0).await.unwrap();
+        assert_eq!(
+            messages.iter().map(|m| m.msg_number).collect::<Vec<_>>(),
+            vec![Some(2), Some(4)]
+        );
+    }
+
+    #[tokio::test]
+    async fn get_messages_for_project() {
+        let reply = "<msgs>\
+                     <msg><project></project><seqno>1</seqno>\
+                     <body><![CDATA[Starting BOINC client version 8.0.2]]></body></msg>\
+                     <msg><project>Einstein@Home</project><seqno>2</seqno>\
+                     <body><![CDATA[Starting task h1_0001]]></body></msg>\
+                     <msg><project>Rosetta@home</project><seqno>3</seqno>\
+                     <body><![CDATA[Starting task rb_0001]]></body></msg>\
+                     </msgs>";
+        let mut client = mock_client(&[("get_messages", reply)]);
+        let seqnos = |messages: Vec<models::Message>| {
+            messages.iter().map(|m| m.msg_number).collect::<Vec<_>>()
+        };
+        assert_eq!(
+            seqnos(
+                client
+                    .get_messages_for_project(0, "einstein@home", false)
+                    .await
+                    .unwrap()
+            ),
+            vec![Some(2)]
+        );
+        assert_eq!(
+            seqnos(
+                client
+                    .get_messages_for_project(0, "ROSETTA@HOME", true)
+                    .await
+                    .unwrap()
+            ),
+            vec![Some(1), Some(3)]
+        );
+    }
+
+    #[tokio::test]
+    async fn get_results_raw() {
+        let mut client = mock_client(&[(
+            "get_results",
+            "<results><result><name>wu_1_0</name></result></results>",
+        )]);
+        let (results, raw) = client.get_results_raw(false).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].name.as_deref(), Some("wu_1_0"));
+        assert!(raw.contains("<result>"));
+        assert!(!raw.contains("<?xml"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn abort_result_and_wait() {
+        let polls = Arc::new(AtomicUsize::new(0));
+        let mut client = Client::new(tower::service_fn({
+            let polls = polls.clone();
+            move |req: Vec<treexml::Element>| {
+                let reply = match &*req[0].name {
+                    "abort_result" => "<success/>",
+                    "get_results" if polls.fetch_add(1, Ordering::SeqCst) == 0 => {
+                        "<results><result><name>wu_1_0</name><state>2</state>\
+                         <active_task><active_task_state>5</active_task_state></active_task>\
+                         </result></results>"
+                    }
+                    _ => "<results><result><name>wu_1_0</name><state>6</state></result></results>",
+                };
+                let reply = util::parse_node(&format!(
+                    "<boinc_gui_rpc_reply>{reply}</boinc_gui_rpc_reply>"
+                ))
+                .map(|root| root.children);
+                async move { reply }
+            }
+        }));
+
+        client
+            .abort_result_and_wait("https://example.com/", "wu_1_0", Duration::from_secs(10))
+            .await
+            .unwrap();
+        assert_eq!(polls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn attach_account_manager() {
+        let polls = Arc::new(AtomicUsize::new(0));
+        let mut client = Client::new(tower::service_fn({
+            let polls = polls.clone();
+            move |req: Vec<treexml::Element>| {
+                let reply = match &*req[0].name {
+                    "acct_mgr_rpc" => "<success/>",
+                    _ if polls.fetch_add(1, Ordering::SeqCst) == 0 => {
+                        "<acct_mgr_rpc_reply><error_num>-204</error_num></acct_mgr_rpc_reply>"
+                    }
+                    _ => "<acct_mgr_rpc_reply><error_num>-206</error_num></acct_mgr_rpc_reply>",
+                };
+                let reply = util::parse_node(&format!(
+                    "<boinc_gui_rpc_reply>{reply}</boinc_gui_rpc_reply>"
+                ))
+                .map(|root| root.children);
+                async move { reply }
+            }
+        }));
+
+        let outcome = client
+            .attach_account_manager(
+                "https://bam.boincstats.com/",
+                "user",
+                "wrong",
+                None,
+                Duration::from_secs(30),
+            )
+            .await
+            .unwrap();
+        assert_eq!(outcome, models::AmAttachOutcome::BadCredentials);
+        assert_eq!(polls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn attach_account_manager_with_cookie() {
+        let requests = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut client = Client::new(tower::service_fn({
+            let requests = requests.clone();
+            move |req: Vec<treexml::Element>| {
+                let reply = match &*req[0].name {
+                    "acct_mgr_rpc" => "<success/>",
+                    _ => "<acct_mgr_rpc_reply><error_num>0</error_num></acct_mgr_rpc_reply>",
+                };
+                requests.lock().unwrap().extend(req);
+                let reply = util::parse_node(&format!(
+                    "<boinc_gui_rpc_reply>{reply}</boinc_gui_rpc_reply>"
+                ))
+                .map(|root| root.children);
+                async move { reply }
+            }
+        }));
+
+        let outcome = client
+            .attach_account_manager(
+                "https://bam.boincstats.com/",
+                "user",
+                "pw",
+                Some("abc123"),
+                Duration::from_secs(30),
+            )
+            .await
+            .unwrap();
+        assert_eq!(outcome, models::AmAttachOutcome::Success);
+        let requests = requests.lock().unwrap().clone();
+        assert_eq!(requests[0].name, "acct_mgr_rpc");
+        let cookie = requests[0]
+            .children
+            .iter()
+            .find(|c| c.name == "cookie")
+            .unwrap();
+        assert_eq!(cookie.text.as_deref(), Some("abc123"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn connect_to_account_manager_errors() {
+        let mut client = mock_client(&[
+            ("acct_mgr_rpc", "<success/>"),
+            (
+                "acct_mgr_rpc_poll",
+                "<acct_mgr_rpc_reply><error_num>-206</error_num></acct_mgr_rpc_reply>",
+            ),
+        ]);
+        assert!(matches!(
+            client
+                .connect_to_account_manager(
+                    "https://bam.boincstats.com/",
+                    "user",
+                    "wrong",
+                    None,
+                    Duration::from_secs(30)
+                )
+                .await,
+            Err(Error::InvalidPassword(_))
+        ));
+
+        let mut client = mock_client(&[("acct_mgr_rpc", "<error>bad url</error>")]);
+        assert_eq!(
+            client
+                .connect_to_account_manager("nonsense", "user", "pw", None, Duration::from_secs(30))
+                .await,
+            Err(Error::InvalidURL("bad url".into()))
+        );
+
+        let mut client = mock_client(&[
+            ("acct_mgr_rpc", "<success/>"),
+            (
+                "acct_mgr_rpc_poll",
+                "<acct_mgr_rpc_reply><error_num>-204</error_num></acct_mgr_rpc_reply>",
+            ),
+        ]);
+        assert!(matches!(
+            client
+                .connect_to_account_manager(
+                    "https://bam.boincstats.com/",
+                    "user",
+                    "pw",
+                    None,
+                    Duration::from_secs(5)
+                )
+                .await,
+            Err(Error::Timeout(_))
+        ));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn connect_and_verify_account_manager() {
+        let mut client = mock_client(&[
+            ("acct_mgr_rpc", "<success/>"),
+            (
+                "acct_mgr_rpc_poll",
+                "<acct_mgr_rpc_reply><error_num>0</error_num></acct_mgr_rpc_reply>",
+            ),
+            (
+                "acct_mgr_info",
+                "<acct_mgr_info>\
+                 <acct_mgr_url>https://bam.boincstats.com/</acct_mgr_url>\
+                 <acct_mgr_name>BAM!</acct_mgr_name>\
+                 <have_credentials/>\
+                 </acct_mgr_info>",
+            ),
+        ]);
+        let info = client
+            .connect_and_verify_account_manager(
+                "https://bam.boincstats.com/",
+                "user",
+                "pw",
+                Some("abc123"),
+                Duration::from_secs(30),
+            )
+            .await
+            .unwrap();
+        assert_eq!(info.url.as_deref(), Some("https://bam.boincstats.com/"));
+        assert_eq!(info.name.as_deref(), Some("BAM!"));
+        assert_eq!(info.have_credentials, Some(true));
+    }
+
+    #[tokio::test]
+    async fn client_clone_shares_transport() {
+        let mut client =
+            mock_client(&[("get_messages", "<msgs><msg><seqno>1</seqno></msg></msgs>")]);
+        let mut other = client.clone();
+        assert_eq!(client.get_messages(0).await.unwrap().len(), 1);
+        assert_eq!(other.get_messages(0).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn snooze() {
+        let (mut client, requests) = recording_client();
+        client
+            .snooze_network(Duration::from_hours(1))
+            .await
+            .unwrap();
+        client.unsnooze_gpu().await.unwrap();
+
+        let requests = requests.lock().unwrap().clone();
+        assert_eq!(requests[0].name, "set_network_mode");
+        assert_eq!(requests[0].children[0].name, "duration");
+        assert_eq!(requests[0].children[0].text.as_deref(), Some("3600"));
+        assert_eq!(requests[0].children[1].name, "never");
+        assert_eq!(requests[1].name, "set_gpu_mode");
+        assert_eq!(requests[1].children[0].text.as_deref(), Some("0"));
+        assert_eq!(requests[1].children[1].name, "restore");
+    }
+
+    #[tokio::test]
+    async fn get_new_messages_tracks_seqno() {
+        let requested = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let mut client = Client::new(tower::service_fn({
+            let requested = requested.clone();
+            move |req: Vec<treexml::Element>| {
+                let seqno = req[0].text.clone().unwrap_or_default();
+                let reply = if seqno == "0" {
+                    "<msgs><msg><seqno>1</seqno></msg><msg><seqno>2</seqno></msg></msgs>"
+                } else {
+                    "<msgs><msg><seqno>3</seqno></msg></msgs>"
+                };
+                requested.lock().unwrap().push(seqno);
+                let reply = util::parse_node(&format!(
+                    "<boinc_gui_rpc_reply>{reply}</boinc_gui_rpc_reply>"
+                ))
+                .map(|root| root.children);
+                async move { reply }
+            }
+        }));
+
+        assert_eq!(client.get_new_messages().await.unwrap().len(), 2);
+        assert_eq!(client.get_new_messages().await.unwrap().len(), 1);
+        assert_eq!(client.last_seqno, 3);
+        assert_eq!(*requested.lock().unwrap(), vec!["0", "2"]);
+    }
+
+    #[tokio::test]
+    async fn get_state_decodes_sections() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let mut conn = BoincCodec::new(CodecMode::Server).framed(socket);
+            while let Ok(Some(_)) = conn.try_next().await {
+                let state = util::parse_node(
+                    "<client_state>\
+                     <host_info><p_ncpus>8</p_ncpus></host_info>\
+                     <platform>x86_64-pc-linux-gnu</platform>\
+                     <project><master_url>https://einstein.phys.uwm.edu/</master_url></project>\
+                     <app><name>einstein_O3AS</name></app>\
+                     <file_info><name>h1_0</name><status>1</status></file_info>\
+                     <app_version><app_name>einstein_O3AS</app_name></app_version>\
+                     <workunit><name>h1_0</name></workunit>\
+                     <result><name>h1_0_0</name><wu_name>h1_0</wu_name></result>\
+                     <time_stats><on_frac>0.99</on_frac></time_stats>\
+                     </client_state>",
+                )
+                .unwrap();
+                if conn.send(vec![state]).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut client = Client::new(Transport::new(addr, None::<&str>));
+        let state = client.get_state().await.unwrap();
+        assert_eq!(state.host_info.as_ref().and_then(|h| h.p_ncpus), Some(8));
+        assert_eq!(
+            state.platforms,
+            [models::Platform::from("x86_64-pc-linux-gnu")]
+        );
+        assert_eq!(
+            state.app_versions[0].project_url.as_deref(),
+            Some("https://einstein.phys.uwm.edu/")
+        );
+        assert_eq!(
+            state
+                .workunit_for(&state.results[0])
+                .and_then(|w| w.name.as_deref()),
+            Some("h1_0")
+        );
+        drop(client);
+    }
+
+    #[tokio::test]
+    async fn transport_retries_failed_connect() {
+        let addr = {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+            listener.local_addr().unwrap()
+        };
+        let mut client = Client::new(Transport::new(addr, None::<&str>));
+
+        let first = client.set_language("en_US").await.err().unwrap();
+        assert!(matches!(first, Error::Network(_)), "{first:?}");
+        assert_eq!(client.set_language("en_US").await.err(), Some(first));
+
+        // Once the daemon is back, the same slot connects.
+        let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+        tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let mut conn = BoincCodec::new(CodecMode::Server).framed(socket);
+            while let Ok(Some(_)) = conn.try_next().await {
+                if conn
+                    .send(vec![treexml::Element::new("success")])
+                    .await
+                    .is_err()
+                {
+                    break;
+                }
+            }
+        });
+        client.set_language("en_US").await.unwrap();
+        drop(client);
+    }
+
+    #[tokio::test]
+    async fn unauthenticated_connection() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (socket, _) = listener.accept().await.unwrap();
+            let mut conn = BoincCodec::new(CodecMode::Server).framed(socket);
+            while let Ok(Some(req)) = conn.try_next().await {
+                // A daemon with a password set: read-only RPCs only.
+                let reply = match &*req[0].name {
+                    "exchange_versions" => util::parse_node(
+                        "<server_version><major>8</major><minor>0</minor><release>4</release></server_version>",
+                    )
+                    .unwrap(),
+                    _ => treexml::Element::new("unauthorized"),
+                };
+                if conn.send(vec![reply]).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut client = Client::new(Transport::new(addr, None::<&str>));
+        let version = client
+            .exchange_versions(&models::VersionInfo::default())
+            .await
+            .unwrap();
+        assert_eq!(version.major, Some(8));
+        assert_eq!(
+            client.get_host_info().await,
+            Err(Error::Auth(String::new()))
+        );
+        drop(client);
+    }
+
+    #[tokio::test]
+    async fn host_info_matches_get_state() {
+        const HOST_INFO: &str = "<host_info>\
+             <domain_name>worker-07</domain_name>\
+             <p_ncpus>16</p_ncpus>\
+             <p_vendor>AuthenticAMD</p_vendor>\
+             <m_nbytes>67352510464.000000</m_nbytes>\
+             <os_name>Linux Ubuntu</os_name>\
+             <os_version>Ubuntu 24.04 LTS</os_version>\
+             <product_name> ASUS PRIME X570-P </product_name>\
+             </host_info>";
+        let state = format!("<client_state>{HOST_INFO}</client_state>");
+        let mut client = mock_client(&[("get_host_info", HOST_INFO), ("get_state", &state)]);
+
+        let host_info = client.get_host_info().await.unwrap();
+        assert_eq!(host_info.p_ncpus, Some(16));
+        assert_eq!(client.get_state().await.unwrap().host_info, Some(host_info));
+    }
+
+    /// A daemon that reports each request as `(connection, request)` and
+    /// answers it with `reply`, or not at all when `reply` returns `None`,
+    /// which drops the connection if `drop_unanswered` is set.
+    async fn event_daemon(
+        reply: impl Fn(usize, &str) -> Option<&'static str> + Send + Sync + 'static,
+        drop_unanswered: bool,
+    ) -> (
+        std::net::SocketAddr,
+        tokio::sync::mpsc::UnboundedReceiver<(usize, String)>,
+    ) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (events, received) = tokio::sync::mpsc::unbounded_channel();
+        let reply = Arc::new(reply);
+        tokio::spawn(async move {
+            let mut n = 0;
+            while let Ok((socket, _)) = listener.accept().await {
+                let (events, reply) = (events.clone(), reply.clone());
+                tokio::spawn(async move {
+                    let mut conn = BoincCodec::new(CodecMode::Server).framed(socket);
+                    while let Ok(Some(req)) = conn.try_next().await {
+                        let name = req[0].name.clone();
+                        let answer = reply(n, &name);
+                        events.send((n, name)).ok();
+                        match answer {
+                            Some(answer) => {
+                                let answer = vec![treexml::Element::new(answer)];
+                                if conn.send(answer).await.is_err() {
+                                    break;
+                                }
+                            }
+                            None if drop_unanswered => break,
+                            None => {}
+                        }
+                    }
+                    // Keep an unanswering connection open until the test ends.
+                    std::future::pending::<()>().await;
+                });
+                n += 1;
+            }
+        });
+        (addr, received)
+    }
+
+    /// Waits for the next request the daemon saw named `name`.
+    async fn next_request(
+        received: &mut tokio::sync::mpsc::UnboundedReceiver<(usize, String)>,
+        name: &str,
+    ) -> usize {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let (n, req) = received.recv().await.unwrap();
+                if req == name {
+                    return n;
+                }
+            }
+        })
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn transport_keepalive() {
+        // Drop the first connection at its first ping, as if it had idled out.
+        let (addr, mut received) = event_daemon(
+            |n, req| match (n, req) {
+                (0, "get_cc_status") => None,
+                (_, "get_cc_status") => Some("cc_status"),
+                _ => Some("success"),
+            },
+            true,
+        )
+        .await;
+
+        let mut client = Client::new(
+            Transport::new(addr, None::<&str>).with_keepalive(Duration::from_millis(20)),
+        );
+        client.set_language("en_US").await.unwrap();
+        assert_eq!(next_request(&mut received, "get_cc_status").await, 0);
+
+        // The reset connection is reopened, and then pinged in turn.
+        client.set_language("en_US").await.unwrap();
+        assert_eq!(next_request(&mut received, "get_cc_status").await, 1);
+        assert_eq!(next_request(&mut received, "get_cc_status").await, 1);
+        drop(client);
+    }
+
+    #[tokio::test]
+    async fn transport_keepalive_ping_timeout() {
+        // The first connection never answers its ping.
+        let (addr, mut received) = event_daemon(
+            |n, req| match (n, req) {
+                (0, "get_cc_status") => None,
+                _ => Some("success"),
+            },
+            false,
+        )
+        .await;
+
+        let mut client = Client::new(
+            Transport::new(addr, None::<&str>).with_keepalive(Duration::from_millis(20)),
+        );
+        client.set_language("en_US").await.unwrap();
+        assert_eq!(next_request(&mut received, "get_cc_status").await, 0);
+
+        // The stuck ping gives the slot up, so this goes over a new connection.
+        tokio::time::timeout(Duration::from_secs(5), client.set_language("en_US"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(next_request(&mut received, "set_language").await, 1);
+        drop(client);
+    }
+
+    #[tokio::test]
+    async fn transport_call_reconnects_idle_slot() {
+        let (addr, _) = fake_daemon().await;
+        let mut transport = Transport::new(addr, None::<&str>);
+        tower::ServiceExt::<Vec<treexml::Element>>::ready(&mut transport)
+            .await
+            .unwrap();
+
+        // As if a keepalive ping failed between `poll_ready` and `call`.
+        *transport.slots[0].lock().await = Some(ConnState::Idle {
+            addr: addr.to_string(),
+            password: None,
+        });
+        let reply =
+            tower::Service::call(&mut transport, vec![treexml::Element::new("set_language")])
+                .await
+                .unwrap();
+        drop(transport);
+        assert_eq!(reply[0].name, "success");
+    }
+
+    #[tokio::test]
+    async fn replays_only_idempotent_requests() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let seen = Arc::new(std::sync::Mutex::new(Vec::<String>::new()));
+        tokio::spawn({
+            let seen = seen.clone();
+            async move {
+                while let Ok((socket, _)) = listener.accept().await {
+                    let seen = seen.clone();
+                    tokio::spawn(async move {
+                        let mut conn = BoincCodec::new(CodecMode::Server).framed(socket);
+                        while let Ok(Some(req)) = conn.try_next().await {
+                            let name = req[0].name.clone();
+                            let first = {
+                                let mut seen = seen.lock().unwrap();
+                                let first = !seen.contains(&name);
+                                seen.push(name.clone());
+                                first
+                            };
+                            // Drop the connection the first time each request
+                            // arrives, before replying to it.
+                            if first {
+                                break;
+                            }
+                            let reply = match &*name {
+                                "get_cc_status" => "cc_status",
+                                _ => "success",
+                            };
+                            if conn.send(vec![treexml::Element::new(reply)]).await.is_err() {
+                                break;
+                            }
+                        }
+                    });
+                }
+            }
+        });
+
+        let mut client = Client::new(Transport::new(addr, None::<&str>));
+        client.get_cc_status().await.unwrap();
+        assert!(client.set_language("en_US").await.is_err());
+        client.set_language("en_US").await.unwrap();
+        assert_eq!(
+            *seen.lock().unwrap(),
+            [
+                "get_cc_status",
+                "get_cc_status",
+                "set_language",
+                "set_language"
+            ]
+        );
+        drop(client);
+    }
+
+    #[tokio::test]
+    async fn bad_replies_are_not_replayed() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let mut client = Client::new(tower::service_fn({
+            let calls = calls.clone();
+            move |_: Vec<treexml::Element>| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                async {
+                    Err::<Vec<treexml::Element>, _>(Error::DataParse("Reply too large".into()))
+                }
+            }
+        }));
+        assert_eq!(
+            client.get_cc_status().await,
+            Err(Error::DataParse("Reply too large".into()))
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn idempotent_requests() {
+        for rpc in [
+            "get_results",
+            "get_cc_status",
+            "exchange_versions",
+            "acct_mgr_info",
+            "acct_mgr_rpc_poll",
+        ] {
+            assert!(super::is_idempotent(rpc), "{rpc}");
+        }
+        for rpc in [
+            "set_run_mode",
+            "abort_result",
+            "project_detach",
+            "acct_mgr_rpc",
+            "read_global_prefs_override",
+        ] {
+            assert!(!super::is_idempotent(rpc), "{rpc}");
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn projects_cache() {
+        let fetches = Arc::new(AtomicUsize::new(0));
+        let mut client = Client::new(tower::service_fn({
+            let fetches = fetches.clone();
+            move |_req: Vec<treexml::Element>| {
+                fetches.fetch_add(1, Ordering::SeqCst);
+                let reply = util::parse_node(
+                    "<boinc_gui_rpc_reply><projects>\
+                     <project><name>Einstein@Home</name></project>\
+                     </projects></boinc_gui_rpc_reply>",
+                )
+                .map(|root| root.children);
+                async move { reply }
+            }
+        }))
+        .with_projects_cache(Duration::from_mins(10));
+
+        assert_eq!(client.get_projects().await.unwrap().len(), 1);
+        assert_eq!(client.get_projects().await.unwrap().len(), 1);
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+
+        tokio::time::advance(Duration::from_mins(11)).await;
+        client.get_projects().await.unwrap();
+        assert_eq!(fetches.load(Ordering::SeqCst), 2);
+
+        client.invalidate_projects_cache();
+        client.get_projects().await.unwrap();
+        assert_eq!(fetches.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn projects_uncached_by_default() {
+        let (mut client, requests) = recording_client();
+        client.get_projects().await.ok();
+        client.get_projects().await.ok();
+        assert_eq!(requests.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn set_mode_durations() {
+        let (mut client, requests) = recording_client();
+        client
+            .set_mode(
+                models::Component::CPU,
+                models::RunMode::Never,
+                models::ModeDuration::Permanent,
+            )
+            .await
+            .unwrap();
+        client
+            .set_mode(
+                models::Component::CPU,
+                models::RunMode::Never,
+                models::ModeDuration::For(Duration::from_millis(1500)),
+            )
+            .await
+            .unwrap();
+        client.snooze_cpu(Duration::ZERO).await.unwrap();
+        let durations = requests
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|r| r.children[0].text.clone().unwrap())
+            .collect::<Vec<_>>();
+        // A zero snooze must not turn into a permanent change.
+        assert_eq!(durations, ["0", "1.5", "1"]);
+    }
+
+    #[tokio::test]
+    async fn set_mode_all() {
+        let (mut client, requests) = recording_client();
+        client
+            .set_mode(
+                models::Component::All,
+                models::RunMode::Never,
+                models::ModeDuration::For(Duration::from_mins(1)),
+            )
+            .await
+            .unwrap();
+        let names = requests
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|r| r.name.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec!["set_run_mode", "set_gpu_mode", "set_network_mode"]
+        );
+
+        let mut client = mock_client(&[
+            ("set_run_mode", "<success/>"),
+            ("set_gpu_mode", "<error>unauthorized</error>"),
+        ]);
+        assert_eq!(
+            client
+                .set_mode(
+                    models::Component::All,
+                    models::RunMode::Never,
+                    models::ModeDuration::For(Duration::from_mins(1)),
+                )
+                .await,
+            Err(Error::PartiallyApplied {
+                applied: vec![models::Component::CPU],
+                error: Box::new(Error::Auth("unauthorized".into())),
+            })
+        );
+
+        // Nothing was applied, so the daemon's error comes back as is.
+        let mut client = mock_client(&[("set_run_mode", "<error>unauthorized</error>")]);
+        assert_eq!(
+            client
+                .set_mode(
+                    models::Component::All,
+                    models::RunMode::Never,
+                    models::ModeDuration::For(Duration::from_mins(1)),
+                )
+                .await,
+            Err(Error::Auth("unauthorized".into()))
+        );
+    }
+
+    #[tokio::test]
+    async fn transport_contention_wakes_waiters() {
+        let (addr, _) = fake_daemon().await;
+        let transport = Transport::new(addr, None::<&str>);
+        let local = tokio::task::LocalSet::new();
+        let polls = Arc::new(AtomicUsize::new(0));
+
+        for _ in 0..50 {
+            let mut client = Client::new(transport.clone());
+            let polls = polls.clone();
+            local.spawn_local(async move {
+                for _ in 0..5 {
+                    let mut call = std::pin::pin!(client.set_language("en_US"));
+                    std::future::poll_fn(|cx| {
+                        polls.fetch_add(1, Ordering::SeqCst);
+                        call.as_mut().poll(cx)
+                    })
+                    .await
+                    .unwrap();
+                }
+            });
+        }
+
+        tokio::time::timeout(Duration::from_secs(10), local)
+            .await
+            .unwrap();
+        // Each call is polled a handful of times, not once per scheduler tick.
+        assert!(polls.load(Ordering::SeqCst) < 50 * 5 * 10);
+    }
+
+    #[tokio::test]
+    async fn transport_dropped_request_releases_slot() {
+        // Holds every handshake until the test opens the gate.
+        let (open, gate) = tokio::sync::watch::channel(false);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((socket, _)) = listener.accept().await {
+                let mut gate = gate.clone();
+                tokio::spawn(async move {
+                    let mut conn = BoincCodec::new(CodecMode::Server).framed(socket);
+                    while let Ok(Some(req)) = conn.try_next().await {
+                        let reply = match &*req[0].name {
+                            "auth1" => {
+                                gate.wait_for(|open| *open).await.unwrap();
+                                "authorized"
+                            }
+                            _ => "success",
+                        };
+                        if conn.send(vec![treexml::Element::new(reply)]).await.is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+
+        let transport = Transport::new(addr, Some("secret"));
+        let mut client = Client::new(transport.clone());
+        let mut other = Client::new(transport);
+        // Give up on the request while it is still connecting.
+        assert!(
+            tokio::time::timeout(Duration::from_millis(50), client.set_language("en_US"))
+                .await
+                .is_err()
+        );
+
+        open.send(true).unwrap();
+        tokio::time::timeout(Duration::from_secs(5), other.set_language("en_US"))
+            .await
+            .unwrap()
+            .unwrap();
+        client.set_language("en_US").await.unwrap();
+        drop((client, other));
+    }
+
+    #[test]
+    fn client_futures_are_send() {
+        fn assert_send<T: Send>(_: &T) {}
+
+        let mut client = Client::new(Transport::new("127.0.0.1:31416", None::<&str>));
+        assert_send(&client);
+        assert_send(&client.get_state());
+        assert_send(&client.get_results(false));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn client_call_spawns_on_multi_thread_runtime() {
+        let (addr, _) = fake_daemon().await;
+        let mut client = Client::new(Transport::new(addr, None::<&str>));
+        tokio::spawn(async move { client.set_language("en_US").await })
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn client_clones_share_connection() {
+        let (addr, connections) = fake_daemon().await;
+        let client = Client::new(Transport::new(addr, None::<&str>));
+
+        let calls = (0..2).map(|_| {
+            let mut client = client.clone();
+            tokio::spawn(async move { client.set_language("en_US").await })
+        });
+        for call in futures::future::join_all(calls).await {
+            call.unwrap().unwrap();
+        }
+        drop(client);
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn transport_from_stream() {
+        let (client_io, server_io) = tokio::io::duplex(1024);
+        let server = tokio::spawn(async move {
+            let mut conn = BoincCodec::new(CodecMode::Server).framed(server_io);
+            let req = conn.try_next().await.unwrap().unwrap();
+            assert_eq!(req[0].name, "set_language");
+            conn.send(vec![treexml::Element::new("success")])
+                .await
+                .unwrap();
+        });
+
+        let stream = DaemonStream::connect_io(client_io, None).await.unwrap();
+        let mut client = Client::new(Transport::from_stream(stream));
+        client.set_language("en_US").await.unwrap();
+        server.await.unwrap();
+
+        // The stream is gone and there is nothing to reconnect to.
+        assert!(client.set_language("en_US").await.is_err());
+        drop(client);
+    }
+
+    #[tokio::test]
+    async fn transport_new_lazy() {
+        let (addr, connections) = fake_daemon().await;
+        let mut client = Client::new(Transport::new_lazy(addr, None::<&str>));
+        tokio::task::yield_now().await;
+        assert_eq!(connections.load(Ordering::SeqCst), 0);
+
+        client.set_language("en_US").await.unwrap();
+        client.set_language("en_US").await.unwrap();
+        drop(client);
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn transport_with_connections() {
+        let (addr, connections) = fake_daemon().await;
+        let transport = Transport::with_connections(addr, None::<&str>, 3);
+
+        let calls = (0..6).map(|_| {
+            let mut client = Client::new(transport.clone());
+            tokio::spawn(async move { client.set_language("en_US").await })
+        });
+        for call in futures::future::join_all(calls).await {
+            call.unwrap().unwrap();
+        }
+        assert_eq!(connections.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn get_client_version() {
+        let mut client = mock_client(&[(
+            "exchange_versions",
+            "<server_version><major>7</major><minor>16</minor><release>16</release></server_version>",
+        )]);
+        assert_eq!(client.get_client_version().await.unwrap(), "7.16.16");
+
+        let mut client = mock_client(&[(
+            "exchange_versions",
+            "<server_version><major>8</major></server_version>",
+        )]);
+        assert_eq!(client.get_client_version().await.unwrap(), "8.0.0");
+
+        let mut client = mock_client(&[("exchange_versions", "<server_version/>")]);
+        assert!(client.get_client_version().await.is_err());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn detach_when_done_and_wait() {
+        let polls = Arc::new(AtomicUsize::new(0));
+        let mut client = Client::new(tower::service_fn({
+            let polls = polls.clone();
+            move |req: Vec<treexml::Element>| {
+                let reply = match &*req[0].name {
+                    "project_detach_when_done" => "<success/>".to_string(),
+                    _ if polls.fetch_add(1, Ordering::SeqCst) < 2 => {
+                        "<projects><project><master_url>https://einstein.phys.uwm.edu/</master_url>\
+                         <detach_when_done/></project></projects>"
+                            .to_string()
+                    }
+                    _ => "<projects/>".to_string(),
+                };
+                let reply = util::parse_node(&format!(
+                    "<boinc_gui_rpc_reply>{reply}</boinc_gui_rpc_reply>"
+                ))
+                .map(|root| root.children);
+                async move { reply }
+            }
+        }));
+
+        client
+            .detach_when_done_and_wait("https://einstein.phys.uwm.edu/", Duration::from_mins(1))
+            .await
+            .unwrap();
+        assert_eq!(polls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn detach_when_done_and_wait_times_out() {
+        let mut client = mock_client(&[
+            ("project_detach_when_done", "<success/>"),
+            (
+                "get_project_status",
+                "<projects><project><master_url>https://einstein.phys.uwm.edu/</master_url></project></projects>",
+            ),
+        ]);
+        let res = client
+            .detach_when_done_and_wait("https://einstein.phys.uwm.edu/", Duration::from_mins(1))
+            .await;
+        assert!(matches!(res, Err(Error::Timeout(_))));
+    }
+
+    #[tokio::test]
+    async fn clear_global_prefs_override() {
+        let (mut client, requests) = recording_client();
+        client.clear_global_prefs_override().await.unwrap();
+
+        let requests = requests.lock().unwrap().clone();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].name, "set_global_prefs_override");
+        assert_eq!(requests[0].children.len(), 1);
+        let prefs = &requests[0].children[0];
+        assert_eq!(prefs.name, "global_preferences");
+        assert!(prefs.children.is_empty());
+        assert_eq!(prefs.text, None);
+        assert_eq!(requests[1].name, "read_global_prefs_override");
+    }
+
+    #[tokio::test]
+    async fn set_global_prefs_override_rejects_invalid() {
+        let (mut client, requests) = recording_client();
+        let prefs = models::GlobalPrefsOverride {
+            max_ncpus_pct: Some(-5.0),
+            ..Default::default()
+        };
+        assert!(matches!(
+            client.set_global_prefs_override(&prefs, true).await,
+            Err(Error::DataParse(_))
+        ));
+        assert!(requests.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn set_global_prefs_override() {
+        let (mut client, requests) = recording_client();
+        let prefs = models::GlobalPrefsOverride {
+            max_ncpus_pct: Some(50.0),
+            ..Default::default()
+        };
+        client
+            .set_global_prefs_override(&prefs, false)
+            .await
+            .unwrap();
+        client.read_global_prefs_override().await.unwrap();
+
+        let requests = requests.lock().unwrap().clone();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].name, "set_global_prefs_override");
+        let prefs = &requests[0].children[0];
+        assert_eq!(prefs.children[0].name, "max_ncpus_pct");
+        assert_eq!(prefs.children[0].text.as_deref(), Some("50"));
+        assert_eq!(requests[1].name, "read_global_prefs_override");
+        assert!(requests[1].children.is_empty());
+    }
+
+    #[tokio::test]
+    async fn get_results_grouped_by_wu() {
+        let mut client = mock_client(&[(
+            "get_results",
+            "<results>\
+             <result><name>wu_1_0</name><wu_name>wu_1</wu_name></result>\
+             <result><name>wu_1_1</name><wu_name>wu_1</wu_name></result>\
+             <result><name>wu_2_0</name><wu_name>wu_2</wu_name></result>\
+             <result><name>orphan</name></result>\
+             </results>",
+        )]);
+        let groups = client.get_results_grouped_by_wu().await.unwrap();
+        assert_eq!(groups.len(), 3);
+        let names = groups["wu_1"]
+            .iter()
+            .map(|r| r.name.as_deref().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["wu_1_0", "wu_1_1"]);
+        assert_eq!(groups["wu_2"].len(), 1);
+        assert_eq!(groups[""][0].name.as_deref(), Some("orphan"));
+    }
+
+    #[tokio::test]
+    async fn version_gating() {
+        let mut client = mock_client(&[
+            (
+                "exchange_versions",
+                "<server_version><major>6</major><minor>10</minor><release>58</release></server_version>",
+            ),
+            ("get_daily_xfer_history", "<daily_xfers/>"),
+        ]);
+        assert!(client.get_daily_xfer_history().await.is_ok());
+        assert!(client.server_version().is_none());
+
+        client
+            .exchange_versions(&models::VersionInfo::default())
+            .await
+            .unwrap();
+        assert_eq!(client.server_version().and_then(|v| v.major), Some(6));
+        assert_eq!(
+            client.get_daily_xfer_history().await,
+            Err(Error::Unsupported {
+                rpc: "get_daily_xfer_history".into(),
+                min_version: Some("7.0.0".into()),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn get_results_due_before() {
+        let mut client = mock_client(&[(
+            "get_results",
+            "<results>\
+             <result><name>far</name><report_deadline>1700900000.0</report_deadline></result>\
+             <result><name>later</name><report_deadline>1700050000.0</report_deadline></result>\
+             <result><name>none</name></result>\
+             <result><name>soon</name><report_deadline>1700010000.0</report_deadline></result>\
+             </results>",
+        )]);
+        let names = client
+            .get_results_due_before(1_700_100_000.0)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| r.name.unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["soon", "later"]);
+    }
+
+    #[tokio::test]
+    async fn get_results_filtered() {
+        let mut client = mock_client(&[(
+            "get_results",
+            "<results>\
+             <result><name>running</name><state>2</state></result>\
+             <result><name>crashed</name><state>3</state></result>\
+             <result><name>stuck</name><state>7</state></result>\
+             <result><name>unknown</name></result>\
+             </results>",
+        )]);
+        let filter = models::GetResultsFilter::states(&[
+            models::ResultState::ComputeError,
+            models::ResultState::UploadFailed,
+        ]);
+        let names = client
+            .get_results_filtered(&filter)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| r.name.unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["crashed", "stuck"]);
+
+        let all = client
+            .get_results_filtered(&models::GetResultsFilter::default())
+            .await
+            .unwrap();
+        assert_eq!(all.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn get_result_counts() {
+        let mut client = mock_client(&[(
+            "get_results",
+            "<results>\
+             <result><name>a</name><state>1</state></result>\
+             <result><name>b</name><state>2</state><active_task><fraction_done>0.5</fraction_done></active_task></result>\
+             <result><name>c</name><state>2</state></result>\
+             <result><name>d</name><state>2</state></result>\
+             <result><name>e</name><state>4</state></result>\
+             <result><name>f</name><state>5</state></result>\
+             <result><name>g</name><state>3</state></result>\
+             <result><name>h</name><state>7</state></result>\
+             <result><name>i</name><state>6</state></result>\
+             <result><name>j</name><state>42</state></result>\
+             </results>",
+        )]);
+        assert_eq!(
+            client.get_result_counts().await.unwrap(),
+            models::ResultCounts {
+                downloading: 1,
+                ready_to_run: 2,
+                running: 1,
+                uploading: 1,
+                ready_to_report: 1,
+                errors: 2,
+                aborted: 1,
+                unknown: 1,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn snapshot() {
+        let mut client = mock_client(&[
+            (
+                "get_cc_status",
+                "<cc_status><task_mode>2</task_mode></cc_status>",
+            ),
+            (
+                "get_results",
+                "<results><result><name>wu_1_0</name></result></results>",
+            ),
+            (
+                "get_disk_usage",
+                "<disk_usage_summary><d_total>1000000000.0</d_total></disk_usage_summary>",
+            ),
+        ]);
+        let snapshot = client.snapshot().await.unwrap();
+        assert_eq!(
+            snapshot.cc_status.task_run_mode(),
+            Some(models::RunMode::Auto)
+        );
+        assert_eq!(snapshot.results.len(), 1);
+        assert_eq!(snapshot.disk_usage.d_total, Some(1e9));
+    }
+
+    #[tokio::test]
+    async fn get_results_limited() {
+        let results = (0..1000)
+            .map(|i| format!("<result><name>wu_{i}_0</name></result>"))
+            .collect::<Vec<_>>()
+            .concat();
+        let reply = format!("<results>{results}</results>");
+        let mut client = mock_client(&[("get_results", &reply)]);
+
+        let (results, truncated) = client.get_results_limited(false, 10).await.unwrap();
+        assert_eq!(results.len(), 10);
+        assert_eq!(results[9].name.as_deref(), Some("wu_9_0"));
+        assert!(truncated);
+
+        let (results, truncated) = client.get_results_limited(false, 1000).await.unwrap();
+        assert_eq!(results.len(), 1000);
+        assert!(!truncated);
+    }
+
+    #[tokio::test]
+    async fn result_op_tags() {
+        let (mut client, requests) = recording_client();
+        for op in [
+            models::ResultOp::Suspend,
+            models::ResultOp::Resume,
+            models::ResultOp::Abort,
+        ] {
+            client
+                .result_op(op, "https://einstein.phys.uwm.edu/", "wu_1_0")
+                .await
+                .unwrap();
+        }
+
+        let requests = requests.lock().unwrap().clone();
+        let names = requests.iter().map(|r| r.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            vec!["suspend_result", "resume_result", "abort_result"]
+        );
+        assert_eq!(requests[2].children[0].name, "project_url");
+        assert_eq!(requests[2].children[1].name, "name");
+        assert_eq!(requests[2].children[1].text.as_deref(), Some("wu_1_0"));
+    }
+
+    #[test]
+    fn canonicalize_master_url() {
+        for (url, canonical) in [
+            (
+                "https://einstein.phys.uwm.edu/",
+                "https://einstein.phys.uwm.edu/",
+            ),
+            ("HTTP://Example.com/proj", "http://Example.com/proj/"),
+            ("example.com/Proj/", "http://example.com/Proj/"),
+            (
+                " https://WWW.worldcommunitygrid.org ",
+                "https://WWW.worldcommunitygrid.org/",
+            ),
+        ] {
+            assert_eq!(util::canonicalize_master_url(url), canonical);
+        }
+
+        assert!(util::same_master_url(
+            "http://Example.com/proj/",
+            "example.com/proj"
+        ));
+        assert!(!util::same_master_url(
+            "http://example.com/Proj/",
+            "http://example.com/proj/"
+        ));
+    }
+
+    #[tokio::test]
+    async fn project_op_canonicalizes_url() {
+        let (mut client, requests) = recording_client();
+        client
+            .project_op(models::ProjectOp::Update, "http://Example.com/proj")
+            .await
+            .unwrap();
+        let requests = requests.lock().unwrap().clone();
+        assert_eq!(
+            requests[0].children[0].text.as_deref(),
+            Some("http://Example.com/proj/")
+        );
+    }
 }
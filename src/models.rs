@@ -1,15 +1,28 @@
 use super::util;
+use crate::errors::Error;
 use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 use treexml;
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Component {
+    #[serde(rename = "cpu")]
     CPU,
+    #[serde(rename = "gpu")]
     GPU,
     Network,
+    /// CPU, GPU and network together.
+    All,
 }
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum RunMode {
     Always,
     Auto,
@@ -17,14 +30,84 @@ pub enum RunMode {
     Restore,
 }
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+impl RunMode {
+    /// Decodes a mode as reported in `cc_status`, e.g. `task_mode`.
+    ///
+    /// Only `1`..=`3` are valid here: [`Self::Restore`] is a request to drop a
+    /// temporary override and never appears in a status.
+    #[must_use]
+    pub const fn from_mode_num(n: i32) -> Option<Self> {
+        match n {
+            1 => Some(Self::Always),
+            2 => Some(Self::Auto),
+            3 => Some(Self::Never),
+            _ => None,
+        }
+    }
+
+    /// BOINC's `RUN_MODE_*` number for this mode. [`Self::Restore`] maps to
+    /// `4`, which the daemon only accepts in requests.
+    #[must_use]
+    pub const fn mode_num(self) -> i32 {
+        match self {
+            Self::Always => 1,
+            Self::Auto => 2,
+            Self::Never => 3,
+            Self::Restore => 4,
+        }
+    }
+}
+
+/// How long a mode set with `set_mode` lasts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModeDuration {
+    /// Until the mode is changed again.
+    Permanent,
+    /// For the given time, after which the previous mode is restored.
+    /// Anything shorter than [`Self::MIN_TEMPORARY`] is sent as that.
+    For(Duration),
+}
+
+impl ModeDuration {
+    /// The shortest temporary change sent. The daemon reads a duration of
+    /// `0` as permanent, so `For(Duration::ZERO)` must not be sent as is.
+    pub const MIN_TEMPORARY: Duration = Duration::from_secs(1);
+
+    /// The `<duration>` to send, in seconds; `0` asks for a permanent change.
+    #[must_use]
+    pub const fn as_secs_f64(self) -> f64 {
+        match self {
+            Self::Permanent => 0.0,
+            Self::For(d) if d.as_nanos() < Self::MIN_TEMPORARY.as_nanos() => {
+                Self::MIN_TEMPORARY.as_secs_f64()
+            }
+            Self::For(d) => d.as_secs_f64(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum CpuSched {
     Uninitialized,
     Preempted,
     Scheduled,
 }
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+/// Formats the way the BOINC Manager labels a task's scheduler state.
+impl std::fmt::Display for CpuSched {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Uninitialized => "Ready to start",
+            Self::Preempted => "Waiting to run",
+            Self::Scheduled => "Running",
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ResultState {
     New,
     FilesDownloading,
@@ -36,7 +119,69 @@ pub enum ResultState {
     UploadFailed,
 }
 
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+impl TryFrom<i64> for ResultState {
+    type Error = i64;
+
+    fn try_from(v: i64) -> Result<Self, Self::Error> {
+        Ok(match v {
+            0 => Self::New,
+            1 => Self::FilesDownloading,
+            2 => Self::FilesDownloaded,
+            3 => Self::ComputeError,
+            4 => Self::FilesUploading,
+            5 => Self::FilesUploaded,
+            6 => Self::Aborted,
+            7 => Self::UploadFailed,
+            _ => return Err(v),
+        })
+    }
+}
+
+/// Formats the way the BOINC Manager labels a task's state.
+impl std::fmt::Display for ResultState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::New => "New",
+            Self::FilesDownloading => "Downloading",
+            Self::FilesDownloaded => "Ready to run",
+            Self::ComputeError => "Computation error",
+            Self::FilesUploading => "Uploading",
+            Self::FilesUploaded => "Ready to report",
+            Self::Aborted => "Aborted",
+            Self::UploadFailed => "Upload failed",
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResultOp {
+    Suspend,
+    Resume,
+    /// Sent as `abort_result`, the only abort the protocol has. The client
+    /// asks a running app to quit, kills it if it doesn't exit promptly, and
+    /// reports the task as aborted; progress since the last checkpoint is
+    /// lost. There is no separate graceful or forced variant.
+    Abort,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectOp {
+    Reset,
+    Detach,
+    Update,
+    Suspend,
+    Resume,
+    NoMoreWork,
+    AllowMoreWork,
+    /// Detach once the tasks the client already has are finished and reported.
+    DetachWhenDone,
+    DontDetachWhenDone,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Process {
     Uninitialized = 0,
     Executing = 1,
@@ -46,16 +191,165 @@ pub enum Process {
     CopyPending = 10,
 }
 
-#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+impl TryFrom<i64> for Process {
+    type Error = i64;
+
+    fn try_from(v: i64) -> Result<Self, Self::Error> {
+        Ok(match v {
+            0 => Self::Uninitialized,
+            1 => Self::Executing,
+            9 => Self::Suspended,
+            5 => Self::AbortPending,
+            8 => Self::QuitPending,
+            10 => Self::CopyPending,
+            _ => return Err(v),
+        })
+    }
+}
+
+/// Formats the way the BOINC Manager labels a task's process state.
+impl std::fmt::Display for Process {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Uninitialized => "Ready to start",
+            Self::Executing => "Running",
+            Self::Suspended => "Suspended",
+            Self::AbortPending => "Abort pending",
+            Self::QuitPending => "Quit pending",
+            Self::CopyPending => "Copy pending",
+        })
+    }
+}
+
+/// Whether the client has, or is waiting for, a network connection.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NetworkStatus {
+    Online,
+    /// The client has work to transfer and wants the user to connect.
+    WantConnection,
+    /// The client is done with the network and the user may disconnect.
+    WantDisconnect,
+    /// A DNS lookup is in progress.
+    LookupPending,
+}
+
+impl TryFrom<i64> for NetworkStatus {
+    type Error = i64;
+
+    fn try_from(v: i64) -> Result<Self, Self::Error> {
+        Ok(match v {
+            0 => Self::Online,
+            1 => Self::WantConnection,
+            2 => Self::WantDisconnect,
+            3 => Self::LookupPending,
+            _ => return Err(v),
+        })
+    }
+}
+
+bitflags::bitflags! {
+    /// Why the client has suspended computation, GPU use or network activity.
+    ///
+    /// The daemon reports these as a bitmask, so several reasons may apply at
+    /// once. Codes from 4097 upwards are enumerated values rather than bits
+    /// and decode to the other variants of [`Suspension`].
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+    pub struct SuspendReason: u32 {
+        const BATTERIES = 1;
+        const USER_ACTIVE = 2;
+        const USER_REQ = 4;
+        const TIME_OF_DAY = 8;
+        const BENCHMARKS = 16;
+        const DISK_SIZE = 32;
+        const CPU_THROTTLE = 64;
+        const NO_RECENT_INPUT = 128;
+        const INITIAL_DELAY = 256;
+        const EXCLUSIVE_APP_RUNNING = 512;
+        const CPU_USAGE_LIMIT = 1024;
+        const NETWORK_QUOTA_EXCEEDED = 2048;
+        const OS = 4096;
+    }
+}
+
+/// A `*_suspend_reason` reported in `cc_status`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Suspension {
+    /// Any reasons from the bitmask; empty when nothing is suspended.
+    Reasons(SuspendReason),
+    /// Waiting for a wifi connection.
+    WifiState,
+    BatteryCharging,
+    BatteryOverheated,
+    /// No GUI has kept the client alive recently.
+    NoGuiKeepalive,
+    /// An enumerated code this crate doesn't know.
+    Other(u32),
+}
+
+impl Suspension {
+    /// Decodes a `*_suspend_reason` code.
+    #[must_use]
+    pub const fn from_code(code: u32) -> Self {
+        match code {
+            0..=4096 => Self::Reasons(SuspendReason::from_bits_retain(code)),
+            4097 => Self::WifiState,
+            4098 => Self::BatteryCharging,
+            4099 => Self::BatteryOverheated,
+            4100 => Self::NoGuiKeepalive,
+            _ => Self::Other(code),
+        }
+    }
+
+    /// Whether anything is suspended.
+    #[must_use]
+    pub const fn is_suspended(self) -> bool {
+        !matches!(self, Self::Reasons(r) if r.is_empty())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct VersionInfo {
     pub major: Option<i64>,
     pub minor: Option<i64>,
     pub release: Option<i64>,
 }
 
+impl Default for VersionInfo {
+    /// Version 8.1.0, the client version this crate speaks the protocol of.
+    ///
+    /// The daemon expects the caller's own version in `exchange_versions`, so
+    /// the default describes a current client rather than sending empty tags.
+    fn default() -> Self {
+        Self {
+            major: Some(8),
+            minor: Some(1),
+            release: Some(0),
+        }
+    }
+}
+
+impl VersionInfo {
+    /// Whether this is at least version `major.minor.release`, reading
+    /// missing components as 0.
+    #[must_use]
+    pub fn at_least(&self, major: i64, minor: i64, release: i64) -> bool {
+        (
+            self.major.unwrap_or(0),
+            self.minor.unwrap_or(0),
+            self.release.unwrap_or(0),
+        ) >= (major, minor, release)
+    }
+}
+
 impl From<&treexml::Element> for VersionInfo {
     fn from(node: &treexml::Element) -> Self {
-        let mut e = Self::default();
+        let mut e = Self {
+            major: None,
+            minor: None,
+            release: None,
+        };
         for n in &node.children {
             match &*n.name {
                 "major" => e.major = util::eval_node_contents(n),
@@ -68,7 +362,8 @@ impl From<&treexml::Element> for VersionInfo {
     }
 }
 
-#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct HostInfo {
     pub tz_shift: Option<i64>,
     pub domain_name: Option<String>,
@@ -102,6 +397,75 @@ pub struct HostInfo {
     pub virtualbox_version: Option<String>,
 }
 
+/// Trimmed text or CDATA of a host identity field. Clients without the
+/// information send the tag empty, which is read as absent.
+fn identity_text(n: &treexml::Element) -> Option<String> {
+    util::trimmed_optional(&util::any_text(n)).filter(|v| !v.is_empty())
+}
+
+impl HostInfo {
+    /// Physical memory in bytes.
+    #[must_use]
+    pub fn memory_bytes(&self) -> Option<u64> {
+        self.m_nbytes.and_then(util::bytes_from_f64)
+    }
+
+    /// Swap space in bytes.
+    #[must_use]
+    pub fn swap_bytes(&self) -> Option<u64> {
+        self.m_swap.and_then(util::bytes_from_f64)
+    }
+
+    /// Size of the disk holding the BOINC data directory, in bytes.
+    #[must_use]
+    pub fn disk_total_bytes(&self) -> Option<u64> {
+        self.d_total.and_then(util::bytes_from_f64)
+    }
+
+    /// Free space on the disk holding the BOINC data directory, in bytes.
+    #[must_use]
+    pub fn disk_free_bytes(&self) -> Option<u64> {
+        self.d_free.and_then(util::bytes_from_f64)
+    }
+}
+
+/// A byte quantity, such as one returned by [`HostInfo::memory_bytes`]. With
+/// the `humansize` feature it displays in binary units, e.g. `16106127360`
+/// as "15.0 GiB".
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct ByteSize(pub u64);
+
+impl From<u64> for ByteSize {
+    fn from(v: u64) -> Self {
+        Self(v)
+    }
+}
+
+impl From<ByteSize> for u64 {
+    fn from(v: ByteSize) -> Self {
+        v.0
+    }
+}
+
+#[cfg(feature = "humansize")]
+impl std::fmt::Display for ByteSize {
+    #[allow(clippy::cast_precision_loss)]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        const UNITS: &[&str] = &["KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+
+        if self.0 < 1024 {
+            return write!(f, "{} B", self.0);
+        }
+        let mut value = self.0 as f64 / 1024.0;
+        let mut unit = 0;
+        while value >= 1024.0 && unit < UNITS.len() - 1 {
+            value /= 1024.0;
+            unit += 1;
+        }
+        write!(f, "{value:.1} {}", UNITS[unit])
+    }
+}
+
 impl From<&treexml::Element> for HostInfo {
     fn from(node: &treexml::Element) -> Self {
         let mut e = Self::default();
@@ -112,18 +476,19 @@ impl From<&treexml::Element> for HostInfo {
                 "p_membw" => e.p_membw = util::eval_node_contents(n),
                 "p_calculated" => e.p_calculated = util::eval_node_contents(n),
                 "p_vm_extensions_disabled" => {
-                    e.p_vm_extensions_disabled = util::eval_node_contents(n);
+                    e.p_vm_extensions_disabled = util::eval_bool(n);
                 }
 
                 "host_cpid" => e.host_cpid.clone_from(&n.text),
-                "product_name" => e.product_name.clone_from(&n.text),
+                "serialnum" => e.serialnum = identity_text(n),
+                "product_name" => e.product_name = identity_text(n),
                 "mac_address" => e.mac_address.clone_from(&n.text),
                 "domain_name" => e.domain_name.clone_from(&n.text),
                 "ip_addr" => e.ip_addr.clone_from(&n.text),
                 "p_vendor" => e.p_vendor.clone_from(&n.text),
                 "p_model" => e.p_model.clone_from(&n.text),
                 "os_name" => e.os_name.clone_from(&n.text),
-                "os_version" => e.os_version.clone_from(&n.text),
+                "os_version" => e.os_version = identity_text(n),
                 "virtualbox_version" => e.virtualbox_version.clone_from(&n.text),
                 "p_features" => e.p_features.clone_from(&n.text),
                 "timezone" => e.tz_shift = util::eval_node_contents(n),
@@ -140,7 +505,8 @@ impl From<&treexml::Element> for HostInfo {
     }
 }
 
-#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ProjectInfo {
     pub name: Option<String>,
     pub summary: Option<String>,
@@ -149,8 +515,39 @@ pub struct ProjectInfo {
     pub specific_area: Option<String>,
     pub description: Option<String>,
     pub home: Option<String>,
-    pub platforms: Option<Vec<String>>,
+    pub platforms: Option<Vec<Platform>>,
     pub image: Option<String>,
+    /// Every keyword the project is tagged with, as listed in `<keywords>`.
+    pub keywords: Vec<String>,
+    /// Science area keywords, from `<sci>` entries.
+    pub sci_keywords: Vec<String>,
+    /// Location keywords, such as the host institution's country, from
+    /// `<loc>` entries.
+    pub loc_keywords: Vec<String>,
+}
+
+impl ProjectInfo {
+    /// The project logo as an absolute URL, resolving a relative `image`
+    /// against the project's `url`, or `home` if that is missing.
+    #[must_use]
+    pub fn image_url(&self) -> Option<String> {
+        let image = self.image.as_deref().filter(|v| !v.is_empty())?;
+        if image.starts_with("http://") || image.starts_with("https://") {
+            return Some(image.to_string());
+        }
+        let base = self.url.as_deref().or(self.home.as_deref())?;
+        let resolved = image.strip_prefix('/').map_or_else(
+            || format!("{}/{image}", base.trim_end_matches('/')),
+            |path| {
+                let scheme_end = base.find("://").map_or(0, |i| i + 3);
+                let origin = base[scheme_end..]
+                    .find('/')
+                    .map_or(base, |i| &base[..scheme_end + i]);
+                format!("{origin}/{path}")
+            },
+        );
+        Some(resolved)
+    }
 }
 
 impl From<&treexml::Element> for ProjectInfo {
@@ -179,12 +576,30 @@ impl From<&treexml::Element> for ProjectInfo {
                 "home" => {
                     e.home = util::trimmed_optional(&util::any_text(n));
                 }
-                "platfroms" => {
+                "keywords" => {
+                    // Either a list of keyword ids as text, or one element
+                    // per keyword.
+                    if let Some(text) = util::any_text(n) {
+                        e.keywords.extend(text.split_whitespace().map(String::from));
+                    }
+                    for k in &n.children {
+                        let Some(v) = util::trimmed_optional(&util::any_text(k)) else {
+                            continue;
+                        };
+                        match &*k.name {
+                            "sci" => e.sci_keywords.push(v.clone()),
+                            "loc" => e.loc_keywords.push(v.clone()),
+                            _ => {}
+                        }
+                        e.keywords.push(v);
+                    }
+                }
+                "platforms" => {
                     let mut platforms = Vec::new();
                     for platform_node in &n.children {
-                        if platform_node.name == "platform" {
+                        if platform_node.name == "name" || platform_node.name == "platform" {
                             if let Some(v) = &platform_node.text {
-                                platforms.push(v.clone());
+                                platforms.push(Platform::from(v.as_str()));
                             }
                         }
                     }
@@ -201,7 +616,8 @@ impl From<&treexml::Element> for ProjectInfo {
     }
 }
 
-#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AccountManagerInfo {
     pub url: Option<String>,
     pub name: Option<String>,
@@ -218,10 +634,10 @@ impl From<&treexml::Element> for AccountManagerInfo {
                 "acct_mgr_url" => e.url = util::trimmed_optional(&util::any_text(n)),
                 "acct_mgr_name" => e.name = util::trimmed_optional(&util::any_text(n)),
                 "have_credentials" => {
-                    e.have_credentials = Some(true);
+                    e.have_credentials = util::eval_bool(n);
                 }
                 "cookie_required" => {
-                    e.cookie_required = Some(true);
+                    e.cookie_required = util::eval_bool(n);
                 }
                 "cookie_failure_url" => {
                     e.cookie_failure_url = util::trimmed_optional(&util::any_text(n));
@@ -233,7 +649,133 @@ impl From<&treexml::Element> for AccountManagerInfo {
     }
 }
 
-#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+/// Final outcome of an account manager RPC, decoded from its `error_num`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AmAttachOutcome {
+    Success,
+    BadCredentials,
+    NetworkError,
+    Failed(i32),
+}
+
+impl From<i32> for AmAttachOutcome {
+    fn from(error_num: i32) -> Self {
+        use BoincErrorCode as E;
+
+        if error_num == 0 {
+            return Self::Success;
+        }
+        match BoincErrorCode::from_i32(error_num) {
+            Some(E::BadUserName | E::BadEmailAddr | E::BadPasswd) => Self::BadCredentials,
+            Some(
+                E::Connect
+                | E::GetHostByName
+                | E::NoNetworkConnection
+                | E::HttpPermanent
+                | E::HttpTransient,
+            ) => Self::NetworkError,
+            _ => Self::Failed(error_num),
+        }
+    }
+}
+
+/// A well-known negative `error_num`, as defined by BOINC's
+/// `lib/error_numbers.h`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[repr(i32)]
+pub enum BoincErrorCode {
+    Connect = -107,
+    Fopen = -108,
+    XmlParse = -112,
+    GetHostByName = -113,
+    GiveupDownload = -114,
+    GiveupUpload = -115,
+    AlreadyAttached = -130,
+    DbNotFound = -136,
+    Authenticator = -155,
+    NotFound = -161,
+    ProjectDown = -183,
+    BadUserName = -188,
+    InvalidUrl = -189,
+    NoNetworkConnection = -203,
+    InProgress = -204,
+    BadEmailAddr = -205,
+    BadPasswd = -206,
+    NonuniqueEmail = -207,
+    AcctCreationDisabled = -208,
+    HttpPermanent = -224,
+    HttpTransient = -225,
+}
+
+impl BoincErrorCode {
+    /// Looks up `code`; codes not in the table yield `None`.
+    #[must_use]
+    pub const fn from_i32(code: i32) -> Option<Self> {
+        Some(match code {
+            -107 => Self::Connect,
+            -108 => Self::Fopen,
+            -112 => Self::XmlParse,
+            -113 => Self::GetHostByName,
+            -114 => Self::GiveupDownload,
+            -115 => Self::GiveupUpload,
+            -130 => Self::AlreadyAttached,
+            -136 => Self::DbNotFound,
+            -155 => Self::Authenticator,
+            -161 => Self::NotFound,
+            -183 => Self::ProjectDown,
+            -188 => Self::BadUserName,
+            -189 => Self::InvalidUrl,
+            -203 => Self::NoNetworkConnection,
+            -204 => Self::InProgress,
+            -205 => Self::BadEmailAddr,
+            -206 => Self::BadPasswd,
+            -207 => Self::NonuniqueEmail,
+            -208 => Self::AcctCreationDisabled,
+            -224 => Self::HttpPermanent,
+            -225 => Self::HttpTransient,
+            _ => return None,
+        })
+    }
+
+    /// The numeric code, e.g. `-206` for [`Self::BadPasswd`].
+    #[must_use]
+    pub const fn code(self) -> i32 {
+        self as i32
+    }
+
+    /// A short description in the wording of BOINC's `boincerror()`.
+    #[must_use]
+    pub const fn describe(self) -> &'static str {
+        match self {
+            Self::Connect => "connect() failed",
+            Self::Fopen => "fopen() failed",
+            Self::XmlParse => "unexpected XML tag or syntax",
+            Self::GetHostByName => "can't resolve hostname",
+            Self::GiveupDownload => "file download timed out",
+            Self::GiveupUpload => "file upload timed out",
+            Self::AlreadyAttached => "already attached to project",
+            Self::DbNotFound => "not found in database",
+            Self::Authenticator => "invalid authenticator",
+            Self::NotFound => "not found",
+            Self::ProjectDown => "project is down",
+            Self::BadUserName => "bad username",
+            Self::InvalidUrl => "invalid URL",
+            Self::NoNetworkConnection => "no network connection",
+            Self::InProgress => "operation in progress",
+            Self::BadEmailAddr => "bad email address",
+            Self::BadPasswd => "bad password",
+            Self::NonuniqueEmail => "email address is already in use",
+            Self::AcctCreationDisabled => "account creation disabled",
+            Self::HttpPermanent => "permanent HTTP error",
+            Self::HttpTransient => "transient HTTP error",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Message {
     pub project_name: Option<String>,
     pub priority: Option<i64>,
@@ -270,11 +812,140 @@ impl From<&treexml::Element> for Message {
     }
 }
 
-#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+impl Message {
+    /// Whether this message records a scheduler RPC, such as
+    /// "Sending scheduler request" or "Scheduler request completed".
+    #[must_use]
+    pub fn is_scheduler_event(&self) -> bool {
+        const PATTERNS: &[&str] = &[
+            "Sending scheduler request",
+            "Scheduler request completed",
+            "Scheduler request failed",
+            "Requesting new tasks",
+            "Not requesting tasks",
+            "Reporting ",
+            "Project requested delay",
+        ];
+        self.project_name.as_ref().is_some_and(|p| !p.is_empty())
+            && self
+                .body
+                .as_ref()
+                .is_some_and(|body| PATTERNS.iter().any(|p| body.contains(p)))
+    }
+
+    /// Scheduler RPC timing mentioned in the message body, if any.
+    ///
+    /// This matches the wording of current clients' event log lines and is
+    /// best effort: a message in another language or from a client that
+    /// phrases it differently yields `None`.
+    #[must_use]
+    pub fn parse_rpc_timing(&self) -> Option<RpcTiming> {
+        let body = self.body.as_deref()?;
+        if let Some(rest) = text_after(body, "Project requested delay of ") {
+            let secs = rest.split_whitespace().next()?.parse::<f64>().ok()?;
+            return Duration::try_from_secs_f64(secs)
+                .ok()
+                .map(RpcTiming::RequestedDelay);
+        }
+        if let Some(rest) = text_after(body, "Deferring communication for ") {
+            return parse_time_diff(rest).map(RpcTiming::Deferral);
+        }
+        if let Some(rest) = text_after(body, "completed in ") {
+            let secs = rest
+                .split_whitespace()
+                .next()?
+                .trim_end_matches(|c: char| c.is_ascii_alphabetic())
+                .parse::<f64>()
+                .ok()?;
+            return Duration::try_from_secs_f64(secs)
+                .ok()
+                .map(RpcTiming::Elapsed);
+        }
+        None
+    }
+}
+
+/// Timing information pulled out of an event log line by
+/// [`Message::parse_rpc_timing`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RpcTiming {
+    /// The project asked the client to wait before contacting it again.
+    RequestedDelay(Duration),
+    /// The client is holding off its next scheduler request for this long.
+    Deferral(Duration),
+    /// How long an operation took.
+    Elapsed(Duration),
+}
+
+fn text_after<'a>(body: &'a str, marker: &str) -> Option<&'a str> {
+    body.find(marker).map(|i| &body[i + marker.len()..])
+}
+
+/// Parses BOINC's `[N days ]HH:MM:SS` time difference format.
+fn parse_time_diff(s: &str) -> Option<Duration> {
+    let mut words = s.split_whitespace();
+    let mut first = words.next()?;
+    let mut secs = 0;
+    if let Some("days" | "day") = words.clone().next() {
+        secs += first.parse::<u64>().ok()? * 86400;
+        words.next();
+        first = words.next()?;
+    }
+    let mut parts = first.split(':').map(|p| p.parse::<u64>().ok());
+    let (h, m, sec) = (parts.next()??, parts.next()??, parts.next()??);
+    if parts.next().is_some() {
+        return None;
+    }
+    Some(Duration::from_secs(secs + h * 3600 + m * 60 + sec))
+}
+
+/// A notice, as shown in the manager's Notices tab.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Notice {
+    pub seqno: Option<i64>,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub create_time: Option<f64>,
+    pub arrival_time: Option<f64>,
+    pub is_private: Option<bool>,
+    pub project_name: Option<String>,
+    pub category: Option<String>,
+    pub link: Option<String>,
+    /// Set when the daemon sends only a `seqno`, meaning the notice with that
+    /// number was removed and should be dropped from any cache.
+    pub is_removal: bool,
+}
+
+impl From<&treexml::Element> for Notice {
+    fn from(node: &treexml::Element) -> Self {
+        let mut e = Self::default();
+        for n in &node.children {
+            match &*n.name {
+                "seqno" => e.seqno = util::eval_node_contents(n),
+                "title" => e.title = util::trimmed_optional(&util::any_text(n)),
+                "description" => e.description = util::trimmed_optional(&util::any_text(n)),
+                "create_time" => e.create_time = util::eval_node_contents(n),
+                "arrival_time" => e.arrival_time = util::eval_node_contents(n),
+                "is_private" => e.is_private = util::eval_bool(n),
+                "project_name" => e.project_name = util::trimmed_optional(&n.text),
+                "category" => e.category = util::trimmed_optional(&n.text),
+                "link" => e.link = util::trimmed_optional(&n.text),
+                _ => {}
+            }
+        }
+        e.is_removal = e.seqno.is_some() && node.children.iter().all(|n| n.name == "seqno");
+        e
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct TaskResult {
     pub name: Option<String>,
     pub wu_name: Option<String>,
-    pub platform: Option<String>,
+    pub platform: Option<Platform>,
     pub version_num: Option<i64>,
     pub plan_class: Option<String>,
     pub project_url: Option<String>,
@@ -286,9 +957,131 @@ pub struct TaskResult {
     pub received_time: Option<f64>,
     pub estimated_cpu_time_remaining: Option<f64>,
     pub completed_time: Option<f64>,
+    /// What the task runs on, as shown by the manager, e.g. "1 CPU + 1 NVIDIA GPU".
+    pub resources: Option<String>,
+    pub scheduler_wait: Option<bool>,
+    pub scheduler_wait_reason: Option<String>,
     pub active_task: Option<ActiveTask>,
 }
 
+impl TaskResult {
+    /// The decoded `state` field.
+    #[must_use]
+    pub fn result_state(&self) -> Option<ResultState> {
+        self.state.and_then(|v| ResultState::try_from(v).ok())
+    }
+
+    /// The task's identity, if both the project URL and name are known.
+    #[must_use]
+    pub fn key(&self) -> Option<ResultKey> {
+        Some(ResultKey {
+            project_url: self.project_url.clone()?,
+            name: self.name.clone()?,
+        })
+    }
+
+    /// Seconds left until `report_deadline` at Unix time `now`; negative once
+    /// the deadline has passed.
+    #[must_use]
+    pub fn time_to_deadline(&self, now: f64) -> Option<f64> {
+        self.report_deadline.map(|t| t - now)
+    }
+
+    /// Whether `report_deadline` is already behind Unix time `now`.
+    #[must_use]
+    pub fn is_past_deadline(&self, now: f64) -> bool {
+        self.time_to_deadline(now).is_some_and(|t| t < 0.0)
+    }
+
+    /// Time since the task was received, as of Unix time `now`.
+    #[must_use]
+    pub fn age(&self, now: f64) -> Option<Duration> {
+        self.received_time.map(|t| saturating_secs(now - t))
+    }
+
+    /// Time left until `report_deadline` at Unix time `now`, zero once the
+    /// deadline has passed.
+    #[must_use]
+    pub fn time_until_deadline(&self, now: f64) -> Option<Duration> {
+        self.time_to_deadline(now).map(saturating_secs)
+    }
+}
+
+/// Which results [`crate::Client::get_results_filtered`] keeps.
+///
+/// The daemon always sends every result, so the filter is applied after
+/// parsing.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GetResultsFilter {
+    /// Only ask for results with an active task.
+    pub active_only: bool,
+    /// Keep only results in one of these states; empty keeps all.
+    pub states: Vec<ResultState>,
+}
+
+impl GetResultsFilter {
+    /// A filter keeping results in any of `states`.
+    #[must_use]
+    pub fn states(states: &[ResultState]) -> Self {
+        Self {
+            states: states.to_vec(),
+            ..Self::default()
+        }
+    }
+
+    /// Whether `result` passes the state filter.
+    #[must_use]
+    pub fn matches(&self, result: &TaskResult) -> bool {
+        self.states.is_empty()
+            || result
+                .result_state()
+                .is_some_and(|state| self.states.contains(&state))
+    }
+}
+
+/// Number of results in each stage of their life, for a compact status line.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResultCounts {
+    /// New or still downloading input files.
+    pub downloading: usize,
+    /// Downloaded and waiting for a CPU or GPU.
+    pub ready_to_run: usize,
+    /// Downloaded and with an active task, including suspended ones.
+    pub running: usize,
+    pub uploading: usize,
+    pub ready_to_report: usize,
+    /// Failed to compute or upload.
+    pub errors: usize,
+    pub aborted: usize,
+    /// Results with a missing or unrecognized state.
+    pub unknown: usize,
+}
+
+impl ResultCounts {
+    #[must_use]
+    pub fn from_results(results: &[TaskResult]) -> Self {
+        let mut counts = Self::default();
+        for result in results {
+            let count = match result.result_state() {
+                Some(ResultState::New | ResultState::FilesDownloading) => &mut counts.downloading,
+                Some(ResultState::FilesDownloaded) if result.active_task.is_some() => {
+                    &mut counts.running
+                }
+                Some(ResultState::FilesDownloaded) => &mut counts.ready_to_run,
+                Some(ResultState::FilesUploading) => &mut counts.uploading,
+                Some(ResultState::FilesUploaded) => &mut counts.ready_to_report,
+                Some(ResultState::ComputeError | ResultState::UploadFailed) => &mut counts.errors,
+                Some(ResultState::Aborted) => &mut counts.aborted,
+                None => &mut counts.unknown,
+            };
+            *count += 1;
+        }
+        counts
+    }
+}
+
 impl From<&treexml::Element> for TaskResult {
     fn from(node: &treexml::Element) -> Self {
         let mut e = Self::default();
@@ -301,7 +1094,7 @@ impl From<&treexml::Element> for TaskResult {
                     e.wu_name = util::trimmed_optional(&n.text);
                 }
                 "platform" => {
-                    e.platform = util::trimmed_optional(&n.text);
+                    e.platform = util::trimmed_optional(&n.text).map(Platform::from);
                 }
                 "version_num" => {
                     e.version_num = util::eval_node_contents(n);
@@ -336,7 +1129,27 @@ impl From<&treexml::Element> for TaskResult {
                 "completed_time" => {
                     e.completed_time = util::eval_node_contents(n);
                 }
+                "resources" => {
+                    e.resources = util::trimmed_optional(&n.text);
+                }
+                "scheduler_wait" => {
+                    e.scheduler_wait = util::eval_bool(n);
+                }
+                "scheduler_wait_reason" => {
+                    e.scheduler_wait_reason = util::trimmed_optional(&n.text);
+                }
                 "active_task" => {
+                    // The client writes the scheduler wait flags inside
+                    // `<active_task>` even though they describe the result.
+                    for c in &n.children {
+                        match &*c.name {
+                            "scheduler_wait" => e.scheduler_wait = util::eval_bool(c),
+                            "scheduler_wait_reason" => {
+                                e.scheduler_wait_reason = util::trimmed_optional(&c.text);
+                            }
+                            _ => {}
+                        }
+                    }
                     e.active_task = Some(ActiveTask::from(n));
                 }
                 _ => {}
@@ -345,7 +1158,8 @@ impl From<&treexml::Element> for TaskResult {
         e
     }
 }
-#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct ActiveTask {
     pub active_task_state: Option<String>,
     pub app_version_num: Option<String>,
@@ -363,6 +1177,32 @@ pub struct ActiveTask {
     pub bytes_sent: Option<f64>,
     pub bytes_received: Option<f64>,
     pub progress_rate: Option<f64>,
+    pub ncpus: Option<f64>,
+    /// Number of GPUs (possibly fractional) the task uses.
+    pub gpu_usage: Option<f64>,
+    /// Index of the GPU the task runs on, among devices of its type.
+    pub gpu_device_num: Option<i64>,
+    /// Full version string including the plan class, e.g. "1.04 (GW-opencl-nvidia-2)".
+    pub app_version: Option<String>,
+}
+
+impl ActiveTask {
+    /// The decoded `active_task_state` field.
+    #[must_use]
+    pub fn process_state(&self) -> Option<Process> {
+        self.active_task_state
+            .as_ref()
+            .and_then(|v| v.parse::<i64>().ok())
+            .and_then(|v| Process::try_from(v).ok())
+    }
+
+    /// The task's working directory, `<data dir>/slots/<slot>`, where files
+    /// such as `stderr.txt` and checkpoints live.
+    #[must_use]
+    pub fn slot_dir(&self, boinc_data_dir: &Path) -> Option<PathBuf> {
+        self.slot
+            .map(|slot| boinc_data_dir.join("slots").join(slot.to_string()))
+    }
 }
 
 impl From<&treexml::Element> for ActiveTask {
@@ -376,6 +1216,18 @@ impl From<&treexml::Element> for ActiveTask {
                 "app_version_num" => {
                     e.app_version_num = util::trimmed_optional(&n.text);
                 }
+                "app_version" => {
+                    e.app_version = util::trimmed_optional(&n.text);
+                }
+                "ncpus" => {
+                    e.ncpus = util::eval_node_contents(n);
+                }
+                "gpu_usage" => {
+                    e.gpu_usage = util::eval_node_contents(n);
+                }
+                "gpu_device_num" => {
+                    e.gpu_device_num = util::eval_node_contents(n);
+                }
                 "slot" => {
                     e.slot = util::eval_node_contents(n);
                 }
@@ -424,3 +1276,2227 @@ impl From<&treexml::Element> for ActiveTask {
         e
     }
 }
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DailyTransfer {
+    /// Day number, counted in days since the Unix epoch.
+    pub when: Option<i64>,
+    pub up: Option<f64>,
+    pub down: Option<f64>,
+}
+
+impl From<&treexml::Element> for DailyTransfer {
+    fn from(node: &treexml::Element) -> Self {
+        let mut e = Self::default();
+        for n in &node.children {
+            match &*n.name {
+                "when" => e.when = util::eval_node_contents(n),
+                "up" => e.up = util::eval_node_contents(n),
+                "down" => e.down = util::eval_node_contents(n),
+                _ => {}
+            }
+        }
+        e
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GlobalPreferences {
+    pub run_on_batteries: Option<bool>,
+    pub run_if_user_active: Option<bool>,
+    pub run_gpu_if_user_active: Option<bool>,
+    pub idle_time_to_run: Option<f64>,
+    pub suspend_cpu_usage: Option<f64>,
+    pub start_hour: Option<f64>,
+    pub end_hour: Option<f64>,
+    pub net_start_hour: Option<f64>,
+    pub net_end_hour: Option<f64>,
+    pub leave_apps_in_memory: Option<bool>,
+    pub dont_verify_images: Option<bool>,
+    pub work_buf_min_days: Option<f64>,
+    pub work_buf_additional_days: Option<f64>,
+    pub max_ncpus_pct: Option<f64>,
+    pub cpu_scheduling_period_minutes: Option<f64>,
+    pub disk_interval: Option<f64>,
+    pub disk_max_used_gb: Option<f64>,
+    pub disk_max_used_pct: Option<f64>,
+    pub disk_min_free_gb: Option<f64>,
+    pub vm_max_used_pct: Option<f64>,
+    pub ram_max_used_busy_pct: Option<f64>,
+    pub ram_max_used_idle_pct: Option<f64>,
+    pub max_bytes_sec_up: Option<f64>,
+    pub max_bytes_sec_down: Option<f64>,
+    pub cpu_usage_limit: Option<f64>,
+    pub daily_xfer_limit_mb: Option<f64>,
+    pub daily_xfer_period_days: Option<i64>,
+}
+
+impl GlobalPreferences {
+    /// Whether the transfers in `history` exceed the daily transfer limit,
+    /// counting the `daily_xfer_period_days` days up to and including `today`.
+    ///
+    /// Mirrors the daemon's own check: a missing or non-positive limit or
+    /// period means transfers are unlimited.
+    #[must_use]
+    pub fn exceeds_xfer_limit(&self, history: &[DailyTransfer], today: i64) -> bool {
+        let (Some(limit_mb), Some(period)) =
+            (self.daily_xfer_limit_mb, self.daily_xfer_period_days)
+        else {
+            return false;
+        };
+        if limit_mb <= 0.0 || period <= 0 {
+            return false;
+        }
+        let total: f64 = history
+            .iter()
+            .filter(|dx| dx.when.is_some_and(|when| when > today - period))
+            .map(|dx| dx.up.unwrap_or_default() + dx.down.unwrap_or_default())
+            .sum();
+        total > limit_mb * 1_048_576.0
+    }
+
+    /// Range-checks the fields the daemon would otherwise clamp or ignore,
+    /// failing with [`Error::DataParse`] naming the first bad field.
+    #[allow(clippy::missing_errors_doc)]
+    pub fn validate(&self) -> Result<(), Error> {
+        let percentages = [
+            ("suspend_cpu_usage", self.suspend_cpu_usage),
+            ("max_ncpus_pct", self.max_ncpus_pct),
+            ("disk_max_used_pct", self.disk_max_used_pct),
+            ("vm_max_used_pct", self.vm_max_used_pct),
+            ("ram_max_used_busy_pct", self.ram_max_used_busy_pct),
+            ("ram_max_used_idle_pct", self.ram_max_used_idle_pct),
+            ("cpu_usage_limit", self.cpu_usage_limit),
+        ];
+        let hours = [
+            ("start_hour", self.start_hour),
+            ("end_hour", self.end_hour),
+            ("net_start_hour", self.net_start_hour),
+            ("net_end_hour", self.net_end_hour),
+        ];
+        let non_negative = [
+            ("idle_time_to_run", self.idle_time_to_run),
+            ("work_buf_min_days", self.work_buf_min_days),
+            ("work_buf_additional_days", self.work_buf_additional_days),
+            (
+                "cpu_scheduling_period_minutes",
+                self.cpu_scheduling_period_minutes,
+            ),
+            ("disk_interval", self.disk_interval),
+            ("disk_max_used_gb", self.disk_max_used_gb),
+            ("disk_min_free_gb", self.disk_min_free_gb),
+            ("max_bytes_sec_up", self.max_bytes_sec_up),
+            ("max_bytes_sec_down", self.max_bytes_sec_down),
+            ("daily_xfer_limit_mb", self.daily_xfer_limit_mb),
+        ];
+
+        let check = |fields: &[(&str, Option<f64>)], min: f64, max: f64| {
+            fields.iter().try_for_each(|(name, value)| match value {
+                Some(v) if !(min..=max).contains(v) => Err(Error::DataParse(format!(
+                    "{name} must be between {min} and {max}, got {v}"
+                ))),
+                _ => Ok(()),
+            })
+        };
+        check(&percentages, 0.0, 100.0)?;
+        check(&hours, 0.0, 24.0)?;
+        check(&non_negative, 0.0, f64::INFINITY)?;
+        if self.daily_xfer_period_days.is_some_and(|v| v < 0) {
+            return Err(Error::DataParse(
+                "daily_xfer_period_days must not be negative".into(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl From<&treexml::Element> for GlobalPreferences {
+    fn from(node: &treexml::Element) -> Self {
+        let mut e = Self::default();
+        for n in &node.children {
+            match &*n.name {
+                "run_on_batteries" => e.run_on_batteries = util::eval_bool(n),
+                "run_if_user_active" => e.run_if_user_active = util::eval_bool(n),
+                "run_gpu_if_user_active" => e.run_gpu_if_user_active = util::eval_bool(n),
+                "idle_time_to_run" => e.idle_time_to_run = util::eval_node_contents(n),
+                "suspend_cpu_usage" => e.suspend_cpu_usage = util::eval_node_contents(n),
+                "start_hour" => e.start_hour = util::eval_node_contents(n),
+                "end_hour" => e.end_hour = util::eval_node_contents(n),
+                "net_start_hour" => e.net_start_hour = util::eval_node_contents(n),
+                "net_end_hour" => e.net_end_hour = util::eval_node_contents(n),
+                "leave_apps_in_memory" => e.leave_apps_in_memory = util::eval_bool(n),
+                "dont_verify_images" => e.dont_verify_images = util::eval_bool(n),
+                "work_buf_min_days" => e.work_buf_min_days = util::eval_node_contents(n),
+                "work_buf_additional_days" => {
+                    e.work_buf_additional_days = util::eval_node_contents(n);
+                }
+                "max_ncpus_pct" => e.max_ncpus_pct = util::eval_node_contents(n),
+                "cpu_scheduling_period_minutes" => {
+                    e.cpu_scheduling_period_minutes = util::eval_node_contents(n);
+                }
+                "disk_interval" => e.disk_interval = util::eval_node_contents(n),
+                "disk_max_used_gb" => e.disk_max_used_gb = util::eval_node_contents(n),
+                "disk_max_used_pct" => e.disk_max_used_pct = util::eval_node_contents(n),
+                "disk_min_free_gb" => e.disk_min_free_gb = util::eval_node_contents(n),
+                "vm_max_used_pct" => e.vm_max_used_pct = util::eval_node_contents(n),
+                "ram_max_used_busy_pct" => e.ram_max_used_busy_pct = util::eval_node_contents(n),
+                "ram_max_used_idle_pct" => e.ram_max_used_idle_pct = util::eval_node_contents(n),
+                "max_bytes_sec_up" => e.max_bytes_sec_up = util::eval_node_contents(n),
+                "max_bytes_sec_down" => e.max_bytes_sec_down = util::eval_node_contents(n),
+                "cpu_usage_limit" => e.cpu_usage_limit = util::eval_node_contents(n),
+                "daily_xfer_limit_mb" => e.daily_xfer_limit_mb = util::eval_node_contents(n),
+                "daily_xfer_period_days" => {
+                    e.daily_xfer_period_days = util::eval_node_contents(n);
+                }
+                _ => {}
+            }
+        }
+        e
+    }
+}
+
+/// Local preferences written with `set_global_prefs_override`. They use the
+/// same fields as the working preferences; unset fields keep the web value.
+pub type GlobalPrefsOverride = GlobalPreferences;
+
+impl From<&GlobalPreferences> for treexml::Element {
+    fn from(prefs: &GlobalPreferences) -> Self {
+        let bool_text = |v: Option<bool>| v.map(|v| if v { "1" } else { "0" }.to_string());
+        let text = |v: Option<f64>| v.map(|v| v.to_string());
+        let fields = [
+            ("run_on_batteries", bool_text(prefs.run_on_batteries)),
+            ("run_if_user_active", bool_text(prefs.run_if_user_active)),
+            (
+                "run_gpu_if_user_active",
+                bool_text(prefs.run_gpu_if_user_active),
+            ),
+            ("idle_time_to_run", text(prefs.idle_time_to_run)),
+            ("suspend_cpu_usage", text(prefs.suspend_cpu_usage)),
+            ("start_hour", text(prefs.start_hour)),
+            ("end_hour", text(prefs.end_hour)),
+            ("net_start_hour", text(prefs.net_start_hour)),
+            ("net_end_hour", text(prefs.net_end_hour)),
+            (
+                "leave_apps_in_memory",
+                bool_text(prefs.leave_apps_in_memory),
+            ),
+            ("dont_verify_images", bool_text(prefs.dont_verify_images)),
+            ("work_buf_min_days", text(prefs.work_buf_min_days)),
+            (
+                "work_buf_additional_days",
+                text(prefs.work_buf_additional_days),
+            ),
+            ("max_ncpus_pct", text(prefs.max_ncpus_pct)),
+            (
+                "cpu_scheduling_period_minutes",
+                text(prefs.cpu_scheduling_period_minutes),
+            ),
+            ("disk_interval", text(prefs.disk_interval)),
+            ("disk_max_used_gb", text(prefs.disk_max_used_gb)),
+            ("disk_max_used_pct", text(prefs.disk_max_used_pct)),
+            ("disk_min_free_gb", text(prefs.disk_min_free_gb)),
+            ("vm_max_used_pct", text(prefs.vm_max_used_pct)),
+            ("ram_max_used_busy_pct", text(prefs.ram_max_used_busy_pct)),
+            ("ram_max_used_idle_pct", text(prefs.ram_max_used_idle_pct)),
+            ("max_bytes_sec_up", text(prefs.max_bytes_sec_up)),
+            ("max_bytes_sec_down", text(prefs.max_bytes_sec_down)),
+            ("cpu_usage_limit", text(prefs.cpu_usage_limit)),
+            ("daily_xfer_limit_mb", text(prefs.daily_xfer_limit_mb)),
+            (
+                "daily_xfer_period_days",
+                prefs.daily_xfer_period_days.map(|v| v.to_string()),
+            ),
+        ];
+
+        let mut node = Self::new("global_preferences");
+        for (name, value) in fields {
+            if let Some(value) = value {
+                let mut child = Self::new(name);
+                child.text = Some(value);
+                node.children.push(child);
+            }
+        }
+        node
+    }
+}
+
+/// A named link published by a project, such as its forum or account page.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GuiUrl {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub url: Option<String>,
+}
+
+impl From<&treexml::Element> for GuiUrl {
+    fn from(node: &treexml::Element) -> Self {
+        let mut e = Self::default();
+        for n in &node.children {
+            match &*n.name {
+                "name" => e.name = util::trimmed_optional(&util::any_text(n)),
+                "description" => e.description = util::trimmed_optional(&util::any_text(n)),
+                "url" => e.url = util::trimmed_optional(&util::any_text(n)),
+                _ => {}
+            }
+        }
+        e
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Project {
+    pub master_url: Option<String>,
+    pub project_name: Option<String>,
+    pub user_name: Option<String>,
+    pub team_name: Option<String>,
+    pub host_venue: Option<String>,
+    pub hostid: Option<i64>,
+    pub user_total_credit: Option<f64>,
+    pub user_expavg_credit: Option<f64>,
+    pub host_total_credit: Option<f64>,
+    pub host_expavg_credit: Option<f64>,
+    pub nrpc_failures: Option<i64>,
+    pub master_fetch_failures: Option<i64>,
+    /// Unix time of the last scheduler RPC, `0` if there has been none.
+    pub last_rpc_time: Option<f64>,
+    /// Unix time the project's files, such as its logo, were last fetched.
+    pub project_files_downloaded_time: Option<f64>,
+    /// Unix time before which the client won't contact the scheduler.
+    pub min_rpc_time: Option<f64>,
+    /// Unix time until which downloads from this project are deferred.
+    pub download_backoff: Option<f64>,
+    /// Unix time until which uploads to this project are deferred.
+    pub upload_backoff: Option<f64>,
+    pub resource_share: Option<f64>,
+    pub sched_priority: Option<f64>,
+    pub suspended_via_gui: Option<bool>,
+    pub dont_request_more_work: Option<bool>,
+    pub attached_via_acct_mgr: Option<bool>,
+    pub detach_when_done: Option<bool>,
+    pub ended: Option<bool>,
+    pub gui_urls: Vec<GuiUrl>,
+}
+
+impl Project {
+    /// Whether the scheduler is deferring contact at Unix time `now`, which is
+    /// the usual reason a project isn't fetching new tasks.
+    #[must_use]
+    pub fn in_backoff(&self, now: f64) -> bool {
+        self.min_rpc_time.is_some_and(|t| t > now)
+    }
+
+    /// Time since the last scheduler RPC, as of Unix time `now`. `None` if the
+    /// client never contacted the scheduler; a long gap may mean it is stuck.
+    #[must_use]
+    pub fn time_since_last_rpc(&self, now: f64) -> Option<Duration> {
+        self.last_rpc_time
+            .filter(|&t| t > 0.0)
+            .map(|t| saturating_secs(now - t))
+    }
+}
+
+impl From<&treexml::Element> for Project {
+    fn from(node: &treexml::Element) -> Self {
+        let mut e = Self::default();
+        for n in &node.children {
+            match &*n.name {
+                "master_url" => e.master_url = util::trimmed_optional(&n.text),
+                "project_name" => e.project_name = util::trimmed_optional(&util::any_text(n)),
+                "user_name" => e.user_name = util::trimmed_optional(&util::any_text(n)),
+                "team_name" => e.team_name = util::trimmed_optional(&util::any_text(n)),
+                "host_venue" => e.host_venue = util::trimmed_optional(&n.text),
+                "hostid" => e.hostid = util::eval_node_contents(n),
+                "user_total_credit" => e.user_total_credit = util::eval_node_contents(n),
+                "user_expavg_credit" => e.user_expavg_credit = util::eval_node_contents(n),
+                "host_total_credit" => e.host_total_credit = util::eval_node_contents(n),
+                "host_expavg_credit" => e.host_expavg_credit = util::eval_node_contents(n),
+                "nrpc_failures" => e.nrpc_failures = util::eval_node_contents(n),
+                "master_fetch_failures" => e.master_fetch_failures = util::eval_node_contents(n),
+                "last_rpc_time" => e.last_rpc_time = util::eval_node_contents(n),
+                "project_files_downloaded_time" => {
+                    e.project_files_downloaded_time = util::eval_node_contents(n);
+                }
+                "min_rpc_time" => e.min_rpc_time = util::eval_node_contents(n),
+                "download_backoff" => e.download_backoff = util::eval_node_contents(n),
+                "upload_backoff" => e.upload_backoff = util::eval_node_contents(n),
+                "resource_share" => e.resource_share = util::eval_node_contents(n),
+                "sched_priority" => e.sched_priority = util::eval_node_contents(n),
+                "suspended_via_gui" => e.suspended_via_gui = util::eval_bool(n),
+                "dont_request_more_work" => e.dont_request_more_work = util::eval_bool(n),
+                "attached_via_acct_mgr" => e.attached_via_acct_mgr = util::eval_bool(n),
+                "detach_when_done" => e.detach_when_done = util::eval_bool(n),
+                "ended" => e.ended = util::eval_bool(n),
+                "gui_urls" => {
+                    e.gui_urls = n
+                        .children
+                        .iter()
+                        .filter(|c| c.name == "gui_url")
+                        .map(GuiUrl::from)
+                        .collect();
+                }
+                _ => {}
+            }
+        }
+        e
+    }
+}
+
+/// A BOINC platform name such as `x86_64-pc-linux-gnu` or `windows_x86_64`.
+///
+/// Serializes as the bare name.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Platform(String);
+
+impl Platform {
+    /// The platform name as the daemon reported it.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// CPU architecture, e.g. `x86_64`.
+    #[must_use]
+    pub fn arch(&self) -> &str {
+        self.0
+            .strip_prefix("windows_")
+            .unwrap_or_else(|| self.0.split('-').next().unwrap_or_default())
+    }
+
+    /// Operating system part, e.g. `linux-gnu`, `darwin` or `windows`.
+    #[must_use]
+    pub fn os(&self) -> &str {
+        if self.0.starts_with("windows_") {
+            return "windows";
+        }
+        // GNU triples: arch-vendor-os, where os may itself contain dashes.
+        self.0.splitn(3, '-').nth(2).unwrap_or_default()
+    }
+}
+
+impl From<&str> for Platform {
+    fn from(v: &str) -> Self {
+        Self(v.into())
+    }
+}
+
+impl From<String> for Platform {
+    fn from(v: String) -> Self {
+        Self(v)
+    }
+}
+
+impl std::fmt::Display for Platform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Whether a plan class such as `cuda`, `opencl_nvidia` or
+/// `GW-opencl-nvidia-2` runs on a GPU. Non-GPU classes like `vbox64` or
+/// `avx` yield `false`.
+#[must_use]
+pub fn is_gpu_plan_class(plan_class: &str) -> bool {
+    const GPU_PREFIXES: &[&str] = &["cuda", "opencl", "nvidia", "ati", "metal", "vulkan"];
+    const GPU_WORDS: &[&str] = &["gpu", "amd", "amdgpu"];
+
+    // Match whole words so that e.g. "optimization" doesn't count as "ati".
+    plan_class
+        .to_ascii_lowercase()
+        .split(['-', '_'])
+        .any(|w| GPU_PREFIXES.iter().any(|p| w.starts_with(p)) || GPU_WORDS.contains(&w))
+}
+
+/// An application version installed on the client, from `get_state`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppVersion {
+    /// Master URL of the project the version belongs to.
+    pub project_url: Option<String>,
+    pub app_name: Option<String>,
+    pub version_num: Option<i64>,
+    pub platform: Option<Platform>,
+    pub plan_class: Option<String>,
+    pub avg_ncpus: Option<f64>,
+    /// Coprocessor vendor, e.g. "NVIDIA", for GPU versions.
+    pub gpu_type: Option<String>,
+    /// Number of GPUs (possibly fractional) a task of this version uses.
+    pub gpu_usage: Option<f64>,
+    pub flops: Option<f64>,
+}
+
+impl From<&treexml::Element> for AppVersion {
+    fn from(node: &treexml::Element) -> Self {
+        let mut e = Self::default();
+        for n in &node.children {
+            match &*n.name {
+                "app_name" => e.app_name = util::trimmed_optional(&n.text),
+                "version_num" => e.version_num = util::eval_node_contents(n),
+                "platform" => e.platform = util::trimmed_optional(&n.text).map(Platform::from),
+                "plan_class" => e.plan_class = util::trimmed_optional(&n.text),
+                "avg_ncpus" => e.avg_ncpus = util::eval_node_contents(n),
+                "flops" => e.flops = util::eval_node_contents(n),
+                "gpu_type" => e.gpu_type = util::trimmed_optional(&n.text),
+                "gpu_usage" => e.gpu_usage = util::eval_node_contents(n),
+                "coproc" => {
+                    for c in &n.children {
+                        match &*c.name {
+                            "type" => e.gpu_type = util::trimmed_optional(&c.text),
+                            "count" => e.gpu_usage = util::eval_node_contents(c),
+                            _ => {}
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        e
+    }
+}
+
+/// A workunit the client has tasks for, from `get_state`.
+///
+/// The resource estimates and bounds apply to every task of the workunit.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Workunit {
+    /// Master URL of the project the workunit belongs to.
+    pub project_url: Option<String>,
+    pub name: Option<String>,
+    pub app_name: Option<String>,
+    /// Estimated floating-point operations to complete a task.
+    pub rsc_fpops_est: Option<f64>,
+    /// Operations after which a task is aborted as runaway.
+    pub rsc_fpops_bound: Option<f64>,
+    /// Bytes of memory a task may use.
+    pub rsc_memory_bound: Option<f64>,
+    /// Bytes of disk a task may use.
+    pub rsc_disk_bound: Option<f64>,
+}
+
+impl From<&treexml::Element> for Workunit {
+    fn from(node: &treexml::Element) -> Self {
+        let mut e = Self::default();
+        for n in &node.children {
+            match &*n.name {
+                "name" => e.name = util::trimmed_optional(&n.text),
+                "app_name" => e.app_name = util::trimmed_optional(&n.text),
+                "rsc_fpops_est" => e.rsc_fpops_est = util::eval_node_contents(n),
+                "rsc_fpops_bound" => e.rsc_fpops_bound = util::eval_node_contents(n),
+                "rsc_memory_bound" => e.rsc_memory_bound = util::eval_node_contents(n),
+                "rsc_disk_bound" => e.rsc_disk_bound = util::eval_node_contents(n),
+                _ => {}
+            }
+        }
+        e
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClientState {
+    pub host_info: Option<HostInfo>,
+    pub projects: Vec<Project>,
+    pub app_versions: Vec<AppVersion>,
+    pub workunits: Vec<Workunit>,
+    pub results: Vec<TaskResult>,
+    /// Platforms the client can run app versions for, e.g.
+    /// `x86_64-pc-linux-gnu` and `i686-pc-linux-gnu`.
+    pub platforms: Vec<Platform>,
+}
+
+impl ClientState {
+    /// The app version `result` runs, matched on project, version number and
+    /// plan class.
+    #[must_use]
+    pub fn app_version_for(&self, result: &TaskResult) -> Option<&AppVersion> {
+        self.app_versions.iter().find(|v| {
+            v.version_num == result.version_num
+                && v.plan_class.as_deref().unwrap_or_default()
+                    == result.plan_class.as_deref().unwrap_or_default()
+                && (v.project_url.is_none()
+                    || result.project_url.is_none()
+                    || v.project_url == result.project_url)
+        })
+    }
+
+    /// The workunit `result` is a task of, matched on `wu_name` and project.
+    #[must_use]
+    pub fn workunit_for(&self, result: &TaskResult) -> Option<&Workunit> {
+        let wu_name = result.wu_name.as_ref()?;
+        self.workunits.iter().find(|w| {
+            w.name.as_ref() == Some(wu_name)
+                && (w.project_url.is_none()
+                    || result.project_url.is_none()
+                    || w.project_url == result.project_url)
+        })
+    }
+}
+
+impl From<&treexml::Element> for ClientState {
+    fn from(node: &treexml::Element) -> Self {
+        let mut e = Self::default();
+        for n in &node.children {
+            match &*n.name {
+                "host_info" => e.host_info = Some(HostInfo::from(n)),
+                "project" => e.projects.push(Project::from(n)),
+                "app_version" => {
+                    // The client lists each project's app versions right
+                    // after the project itself.
+                    let mut version = AppVersion::from(n);
+                    version.project_url = e.projects.last().and_then(|p| p.master_url.clone());
+                    e.app_versions.push(version);
+                }
+                "workunit" => {
+                    // Like app versions, workunits follow their project.
+                    let mut workunit = Workunit::from(n);
+                    workunit.project_url = e.projects.last().and_then(|p| p.master_url.clone());
+                    e.workunits.push(workunit);
+                }
+                "result" => e.results.push(TaskResult::from(n)),
+                "platform" => e
+                    .platforms
+                    .extend(util::trimmed_optional(&n.text).map(Platform::from)),
+                _ => {}
+            }
+        }
+        e
+    }
+}
+
+/// Changes between two [`ClientState`] snapshots.
+///
+/// Projects are identified by master URL and results by name; entries
+/// without an identifier are ignored.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StateDiff {
+    pub added_projects: Vec<String>,
+    pub removed_projects: Vec<String>,
+    pub changed_projects: Vec<String>,
+    pub added_results: Vec<ResultKey>,
+    pub removed_results: Vec<ResultKey>,
+    pub changed_results: Vec<ResultKey>,
+}
+
+impl StateDiff {
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.added_projects.is_empty()
+            && self.removed_projects.is_empty()
+            && self.changed_projects.is_empty()
+            && self.added_results.is_empty()
+            && self.removed_results.is_empty()
+            && self.changed_results.is_empty()
+    }
+}
+
+/// Returns the (added, removed, changed) keys between `previous` and `current`,
+/// in the order they appear in each list.
+fn diff_by_key<T: PartialEq, K: Eq + Hash>(
+    current: &[T],
+    previous: &[T],
+    key: impl Fn(&T) -> Option<K>,
+) -> (Vec<K>, Vec<K>, Vec<K>) {
+    let old: HashMap<K, &T> = previous.iter().filter_map(|v| Some((key(v)?, v))).collect();
+    let mut added = Vec::new();
+    let mut changed = Vec::new();
+    for item in current {
+        let Some(k) = key(item) else { continue };
+        match old.get(&k) {
+            None => added.push(k),
+            Some(&v) if v != item => changed.push(k),
+            Some(_) => {}
+        }
+    }
+    let current: HashSet<K> = current.iter().filter_map(&key).collect();
+    let removed = previous
+        .iter()
+        .filter_map(&key)
+        .filter(|k| !current.contains(k))
+        .collect();
+    (added, removed, changed)
+}
+
+impl ClientState {
+    /// Reports which projects and results were added, removed or changed
+    /// since `previous`.
+    #[must_use]
+    pub fn diff(&self, previous: &Self) -> StateDiff {
+        let (added_projects, removed_projects, changed_projects) =
+            diff_by_key(&self.projects, &previous.projects, |p| p.master_url.clone());
+        let (added_results, removed_results, changed_results) =
+            diff_by_key(&self.results, &previous.results, TaskResult::key);
+        StateDiff {
+            added_projects,
+            removed_projects,
+            changed_projects,
+            added_results,
+            removed_results,
+            changed_results,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CcStatus {
+    pub network_status: Option<NetworkStatus>,
+    pub ams_password_error: Option<bool>,
+    pub manager_must_quit: Option<bool>,
+    pub task_suspend_reason: Option<Suspension>,
+    pub task_mode: Option<i64>,
+    pub task_mode_perm: Option<i64>,
+    pub task_mode_delay: Option<f64>,
+    pub gpu_suspend_reason: Option<Suspension>,
+    pub gpu_mode: Option<i64>,
+    pub gpu_mode_perm: Option<i64>,
+    pub gpu_mode_delay: Option<f64>,
+    pub network_suspend_reason: Option<Suspension>,
+    pub network_mode: Option<i64>,
+    pub network_mode_perm: Option<i64>,
+    pub network_mode_delay: Option<f64>,
+    pub disallow_attach: Option<bool>,
+    pub simple_gui_only: Option<bool>,
+    pub max_event_log_lines: Option<i64>,
+}
+
+/// Converts seconds to a `Duration`, clamping negative and NaN values to zero.
+fn saturating_secs(secs: f64) -> Duration {
+    Duration::try_from_secs_f64(secs).unwrap_or(Duration::ZERO)
+}
+
+/// Converts a BOINC `*_delay` value in seconds; zero means no override is active.
+fn delay_duration(delay: Option<f64>) -> Option<Duration> {
+    delay
+        .filter(|d| *d > 0.0)
+        .and_then(|d| Duration::try_from_secs_f64(d).ok())
+}
+
+impl CcStatus {
+    /// Current CPU run mode, including any temporary override.
+    #[must_use]
+    pub fn task_run_mode(&self) -> Option<RunMode> {
+        run_mode(self.task_mode)
+    }
+
+    /// Current GPU run mode, including any temporary override.
+    #[must_use]
+    pub fn gpu_run_mode(&self) -> Option<RunMode> {
+        run_mode(self.gpu_mode)
+    }
+
+    /// Current network mode, including any temporary override.
+    #[must_use]
+    pub fn network_run_mode(&self) -> Option<RunMode> {
+        run_mode(self.network_mode)
+    }
+
+    /// Time until the temporary task mode override expires.
+    #[must_use]
+    pub fn task_delay_duration(&self) -> Option<Duration> {
+        delay_duration(self.task_mode_delay)
+    }
+
+    /// Time until the temporary GPU mode override expires.
+    #[must_use]
+    pub fn gpu_delay_duration(&self) -> Option<Duration> {
+        delay_duration(self.gpu_mode_delay)
+    }
+
+    /// Time until the temporary network mode override expires.
+    #[must_use]
+    pub fn network_delay_duration(&self) -> Option<Duration> {
+        delay_duration(self.network_mode_delay)
+    }
+}
+
+fn run_mode(mode: Option<i64>) -> Option<RunMode> {
+    mode.and_then(|v| i32::try_from(v).ok())
+        .and_then(RunMode::from_mode_num)
+}
+
+fn suspend_reason(n: &treexml::Element) -> Option<Suspension> {
+    util::eval_node_contents::<i64>(n)
+        .and_then(|v| u32::try_from(v).ok())
+        .map(Suspension::from_code)
+}
+
+impl From<&treexml::Element> for CcStatus {
+    fn from(node: &treexml::Element) -> Self {
+        let mut e = Self::default();
+        for n in &node.children {
+            match &*n.name {
+                "network_status" => {
+                    e.network_status = util::eval_node_contents::<i64>(n)
+                        .and_then(|v| NetworkStatus::try_from(v).ok());
+                }
+                "ams_password_error" => e.ams_password_error = util::eval_bool(n),
+                "manager_must_quit" => e.manager_must_quit = util::eval_bool(n),
+                "task_suspend_reason" => e.task_suspend_reason = suspend_reason(n),
+                "task_mode" => e.task_mode = util::eval_node_contents(n),
+                "task_mode_perm" => e.task_mode_perm = util::eval_node_contents(n),
+                "task_mode_delay" => e.task_mode_delay = util::eval_node_contents(n),
+                "gpu_suspend_reason" => e.gpu_suspend_reason = suspend_reason(n),
+                "gpu_mode" => e.gpu_mode = util::eval_node_contents(n),
+                "gpu_mode_perm" => e.gpu_mode_perm = util::eval_node_contents(n),
+                "gpu_mode_delay" => e.gpu_mode_delay = util::eval_node_contents(n),
+                "network_suspend_reason" => e.network_suspend_reason = suspend_reason(n),
+                "network_mode" => e.network_mode = util::eval_node_contents(n),
+                "network_mode_perm" => e.network_mode_perm = util::eval_node_contents(n),
+                "network_mode_delay" => e.network_mode_delay = util::eval_node_contents(n),
+                "disallow_attach" => e.disallow_attach = util::eval_bool(n),
+                "simple_gui_only" => e.simple_gui_only = util::eval_bool(n),
+                "max_event_log_lines" => e.max_event_log_lines = util::eval_node_contents(n),
+                _ => {}
+            }
+        }
+        e
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProjectDiskUsage {
+    pub master_url: Option<String>,
+    pub disk_usage: Option<f64>,
+}
+
+impl From<&treexml::Element> for ProjectDiskUsage {
+    fn from(node: &treexml::Element) -> Self {
+        let mut e = Self::default();
+        for n in &node.children {
+            match &*n.name {
+                "master_url" => e.master_url = util::trimmed_optional(&n.text),
+                "disk_usage" => e.disk_usage = util::eval_node_contents(n),
+                _ => {}
+            }
+        }
+        e
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiskUsage {
+    pub projects: Vec<ProjectDiskUsage>,
+    pub d_total: Option<f64>,
+    pub d_free: Option<f64>,
+    pub d_boinc: Option<f64>,
+    pub d_allowed: Option<f64>,
+}
+
+impl DiskUsage {
+    /// Bytes used by BOINC itself plus all project directories.
+    #[must_use]
+    pub fn used(&self) -> f64 {
+        self.d_boinc.unwrap_or_default()
+            + self
+                .projects
+                .iter()
+                .filter_map(|p| p.disk_usage)
+                .sum::<f64>()
+    }
+
+    /// Fraction of the allowed quota in use, counting BOINC and its projects
+    /// as [`Self::used`] does. `None` if the quota is unknown or zero.
+    #[must_use]
+    pub fn usage_fraction(&self) -> Option<f64> {
+        self.d_allowed
+            .filter(|allowed| *allowed > 0.0)
+            .map(|allowed| self.used() / allowed)
+    }
+
+    /// Percentage of the allowed quota in use, if the quota is known.
+    #[must_use]
+    pub fn pct_used(&self) -> Option<f64> {
+        self.usage_fraction().map(|f| f * 100.0)
+    }
+
+    /// Whether at least `threshold` (a fraction, e.g. `0.9`) of the allowed
+    /// quota is in use, so BOINC is about to stop fetching work.
+    #[must_use]
+    pub fn is_near_limit(&self, threshold: f64) -> bool {
+        self.usage_fraction().is_some_and(|f| f >= threshold)
+    }
+
+    /// Whether BOINC uses more than its allowed quota, at which point projects
+    /// stop fetching work.
+    #[must_use]
+    pub fn is_over_allowed(&self) -> bool {
+        self.d_allowed.is_some_and(|allowed| self.used() > allowed)
+    }
+}
+
+impl From<&treexml::Element> for DiskUsage {
+    fn from(node: &treexml::Element) -> Self {
+        let mut e = Self::default();
+        for n in &node.children {
+            match &*n.name {
+                "project" => e.projects.push(ProjectDiskUsage::from(n)),
+                "d_total" => e.d_total = util::eval_node_contents(n),
+                "d_free" => e.d_free = util::eval_node_contents(n),
+                "d_boinc" => e.d_boinc = util::eval_node_contents(n),
+                "d_allowed" => e.d_allowed = util::eval_node_contents(n),
+                _ => {}
+            }
+        }
+        e
+    }
+}
+
+/// What a monitoring scrape usually wants, fetched by
+/// [`crate::Client::snapshot`].
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Snapshot {
+    pub cc_status: CcStatus,
+    /// Results with an active task.
+    pub results: Vec<TaskResult>,
+    pub disk_usage: DiskUsage,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PersistentFileXfer {
+    pub num_retries: Option<i64>,
+    pub first_request_time: Option<f64>,
+    pub next_request_time: Option<f64>,
+    pub time_so_far: Option<f64>,
+    pub last_bytes_xferred: Option<f64>,
+    pub is_upload: Option<bool>,
+}
+
+impl PersistentFileXfer {
+    /// Time until the next attempt, given the current Unix time `now`; `None`
+    /// if no retry is pending.
+    #[must_use]
+    pub fn next_retry_in(&self, now: f64) -> Option<Duration> {
+        self.next_request_time
+            .map(|t| t - now)
+            .filter(|d| *d > 0.0)
+            .and_then(|d| Duration::try_from_secs_f64(d).ok())
+    }
+}
+
+impl From<&treexml::Element> for PersistentFileXfer {
+    fn from(node: &treexml::Element) -> Self {
+        let mut e = Self::default();
+        for n in &node.children {
+            match &*n.name {
+                "num_retries" => e.num_retries = util::eval_node_contents(n),
+                "first_request_time" => e.first_request_time = util::eval_node_contents(n),
+                "next_request_time" => e.next_request_time = util::eval_node_contents(n),
+                "time_so_far" => e.time_so_far = util::eval_node_contents(n),
+                "last_bytes_xferred" => e.last_bytes_xferred = util::eval_node_contents(n),
+                "is_upload" => e.is_upload = util::eval_bool(n),
+                _ => {}
+            }
+        }
+        e
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FileXfer {
+    pub bytes_xferred: Option<f64>,
+    pub file_offset: Option<f64>,
+    pub xfer_speed: Option<f64>,
+    pub url: Option<String>,
+}
+
+impl From<&treexml::Element> for FileXfer {
+    fn from(node: &treexml::Element) -> Self {
+        let mut e = Self::default();
+        for n in &node.children {
+            match &*n.name {
+                "bytes_xferred" => e.bytes_xferred = util::eval_node_contents(n),
+                "file_offset" => e.file_offset = util::eval_node_contents(n),
+                "xfer_speed" => e.xfer_speed = util::eval_node_contents(n),
+                "url" => e.url = util::trimmed_optional(&n.text),
+                _ => {}
+            }
+        }
+        e
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferStatus {
+    InProgress,
+    RetryScheduled,
+    Error(i32),
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FileTransfer {
+    pub project_url: Option<String>,
+    pub project_name: Option<String>,
+    pub name: Option<String>,
+    pub nbytes: Option<f64>,
+    pub status: Option<i32>,
+    pub project_backoff: Option<f64>,
+    pub persistent_file_xfer: Option<PersistentFileXfer>,
+    pub file_xfer: Option<FileXfer>,
+}
+
+impl FileTransfer {
+    /// Decodes `status` together with the retry schedule at Unix time `now`.
+    #[must_use]
+    pub fn transfer_status(&self, now: f64) -> TransferStatus {
+        match self.status {
+            Some(code) if code < 0 => TransferStatus::Error(code),
+            _ if self
+                .persistent_file_xfer
+                .as_ref()
+                .and_then(|pfx| pfx.next_retry_in(now))
+                .is_some() =>
+            {
+                TransferStatus::RetryScheduled
+            }
+            _ => TransferStatus::InProgress,
+        }
+    }
+
+    /// Average transfer speed in bytes per second so far, `None` before any
+    /// time has been spent on the transfer.
+    #[must_use]
+    pub fn speed_bps(&self) -> Option<f64> {
+        let pfx = self.persistent_file_xfer.as_ref()?;
+        let elapsed = pfx.time_so_far.filter(|t| *t > 0.0)?;
+        Some(pfx.last_bytes_xferred? / elapsed)
+    }
+
+    /// Estimated seconds until the transfer completes at [`Self::speed_bps`],
+    /// `None` while the speed is unknown or zero.
+    #[must_use]
+    pub fn eta(&self) -> Option<f64> {
+        let speed = self.speed_bps().filter(|s| *s > 0.0)?;
+        let done = self
+            .file_xfer
+            .as_ref()
+            .and_then(|fx| fx.bytes_xferred)
+            .or_else(|| self.persistent_file_xfer.as_ref()?.last_bytes_xferred)?;
+        Some((self.nbytes? - done).max(0.0) / speed)
+    }
+}
+
+impl From<&treexml::Element> for FileTransfer {
+    fn from(node: &treexml::Element) -> Self {
+        let mut e = Self::default();
+        for n in &node.children {
+            match &*n.name {
+                "project_url" => e.project_url = util::trimmed_optional(&n.text),
+                "project_name" => e.project_name = util::trimmed_optional(&util::any_text(n)),
+                "name" => e.name = util::trimmed_optional(&n.text),
+                "nbytes" => e.nbytes = util::eval_node_contents(n),
+                "status" => e.status = util::eval_node_contents(n),
+                "project_backoff" => e.project_backoff = util::eval_node_contents(n),
+                "persistent_file_xfer" => {
+                    e.persistent_file_xfer = Some(PersistentFileXfer::from(n));
+                }
+                "file_xfer" => e.file_xfer = Some(FileXfer::from(n)),
+                _ => {}
+            }
+        }
+        e
+    }
+}
+
+/// Identifies a task across polls.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct ResultKey {
+    pub project_url: String,
+    pub name: String,
+}
+
+/// Proxy configuration, as returned by `get_proxy_settings`.
+///
+/// `Debug` masks the proxy passwords so the struct can be logged safely.
+#[derive(Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProxyInfo {
+    pub use_http_proxy: Option<bool>,
+    pub use_socks_proxy: Option<bool>,
+    pub use_http_auth: Option<bool>,
+    pub http_server_name: Option<String>,
+    pub http_server_port: Option<u16>,
+    pub http_user_name: Option<String>,
+    pub http_user_passwd: Option<String>,
+    pub socks_server_name: Option<String>,
+    pub socks_server_port: Option<u16>,
+    pub socks5_user_name: Option<String>,
+    pub socks5_user_passwd: Option<String>,
+    pub socks5_remote_dns: Option<bool>,
+    pub no_proxy: Option<String>,
+    pub no_autodetect: Option<bool>,
+}
+
+/// Stands in for a secret in `Debug` output while still showing whether one is set.
+#[allow(clippy::ref_option)]
+fn redacted(secret: &Option<String>) -> Option<&'static str> {
+    secret.as_ref().map(|_| "***")
+}
+
+impl std::fmt::Debug for ProxyInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProxyInfo")
+            .field("use_http_proxy", &self.use_http_proxy)
+            .field("use_socks_proxy", &self.use_socks_proxy)
+            .field("use_http_auth", &self.use_http_auth)
+            .field("http_server_name", &self.http_server_name)
+            .field("http_server_port", &self.http_server_port)
+            .field("http_user_name", &self.http_user_name)
+            .field("http_user_passwd", &redacted(&self.http_user_passwd))
+            .field("socks_server_name", &self.socks_server_name)
+            .field("socks_server_port", &self.socks_server_port)
+            .field("socks5_user_name", &self.socks5_user_name)
+            .field("socks5_user_passwd", &redacted(&self.socks5_user_passwd))
+            .field("socks5_remote_dns", &self.socks5_remote_dns)
+            .field("no_proxy", &self.no_proxy)
+            .field("no_autodetect", &self.no_autodetect)
+            .finish()
+    }
+}
+
+impl From<&treexml::Element> for ProxyInfo {
+    fn from(node: &treexml::Element) -> Self {
+        let mut e = Self::default();
+        for n in &node.children {
+            match &*n.name {
+                "use_http_proxy" => e.use_http_proxy = util::eval_bool(n),
+                "use_socks_proxy" => e.use_socks_proxy = util::eval_bool(n),
+                "use_http_auth" => e.use_http_auth = util::eval_bool(n),
+                "http_server_name" => e.http_server_name = util::trimmed_optional(&n.text),
+                "http_server_port" => e.http_server_port = util::eval_node_contents(n),
+                "http_user_name" => e.http_user_name = util::trimmed_optional(&n.text),
+                "http_user_passwd" => e.http_user_passwd.clone_from(&n.text),
+                "socks_server_name" => e.socks_server_name = util::trimmed_optional(&n.text),
+                "socks_server_port" => e.socks_server_port = util::eval_node_contents(n),
+                "socks5_user_name" => e.socks5_user_name = util::trimmed_optional(&n.text),
+                "socks5_user_passwd" => e.socks5_user_passwd.clone_from(&n.text),
+                "socks5_remote_dns" => e.socks5_remote_dns = util::eval_bool(n),
+                "no_proxy" => e.no_proxy = util::trimmed_optional(&n.text),
+                "no_autodetect" => e.no_autodetect = util::eval_bool(n),
+                _ => {}
+            }
+        }
+        e
+    }
+}
+
+/// The commonly used model types, for a single glob import.
+///
+/// ```
+/// use boinc_rpc::models::prelude::*;
+///
+/// let status = CcStatus::default();
+/// assert_eq!(status.task_run_mode(), None::<RunMode>);
+/// let results: Vec<TaskResult> = Vec::new();
+/// assert!(results.iter().all(|r| r.result_state() != Some(ResultState::Aborted)));
+/// let projects: Vec<Project> = Vec::new();
+/// assert!(projects.is_empty());
+/// let _ = (Component::All, RunMode::Never, ModeDuration::Permanent);
+/// ```
+pub mod prelude {
+    pub use super::{
+        AccountManagerInfo, ActiveTask, CcStatus, ClientState, Component, DiskUsage, FileTransfer,
+        HostInfo, Message, ModeDuration, Notice, Project, ProjectInfo, ProjectOp, ResultOp,
+        ResultState, RunMode, Suspension, TaskResult, VersionInfo,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        is_gpu_plan_class, util, AccountManagerInfo, ActiveTask, BoincErrorCode, CcStatus,
+        ClientState, Component, CpuSched, DiskUsage, Error, FileTransfer, GlobalPreferences,
+        GuiUrl, HostInfo, Message, NetworkStatus, Notice, PersistentFileXfer, Platform, Process,
+        Project, ProjectDiskUsage, ProjectInfo, ProxyInfo, ResultKey, ResultState, RpcTiming,
+        RunMode, SuspendReason, Suspension, TaskResult, TransferStatus, VersionInfo,
+    };
+    use std::{
+        path::{Path, PathBuf},
+        time::Duration,
+    };
+
+    #[test]
+    fn task_result_json_round_trip() {
+        let fixture = TaskResult {
+            name: Some("wu_123_0".into()),
+            wu_name: Some("wu_123".into()),
+            version_num: Some(716),
+            state: Some(2),
+            report_deadline: Some(1_700_000_000.5),
+            active_task: Some(ActiveTask {
+                slot: Some(3),
+                fraction_done: Some(0.25),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let json = serde_json::to_string(&fixture).unwrap();
+        assert_eq!(serde_json::from_str::<TaskResult>(&json).unwrap(), fixture);
+    }
+
+    #[test]
+    fn enum_json_representation() {
+        assert_eq!(
+            serde_json::to_string(&Some(RunMode::Auto)).unwrap(),
+            "\"auto\""
+        );
+        assert_eq!(
+            serde_json::to_string(&ResultState::FilesDownloading).unwrap(),
+            "\"files_downloading\""
+        );
+        assert_eq!(serde_json::to_string(&Component::CPU).unwrap(), "\"cpu\"");
+        assert_eq!(
+            serde_json::from_str::<Option<RunMode>>("null").unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn version_info_default() {
+        let v = VersionInfo::default();
+        assert_eq!((v.major, v.minor, v.release), (Some(8), Some(1), Some(0)));
+
+        let mut node = treexml::Element::new("server_version");
+        let mut major = treexml::Element::new("major");
+        major.text = Some("7".into());
+        node.children.push(major);
+        assert_eq!(
+            VersionInfo::from(&node),
+            VersionInfo {
+                major: Some(7),
+                minor: None,
+                release: None,
+            }
+        );
+    }
+
+    #[test]
+    fn host_info_numeric_bool() {
+        let mut node = treexml::Element::new("host_info");
+        let mut flag = treexml::Element::new("p_vm_extensions_disabled");
+        flag.text = Some("1".into());
+        node.children.push(flag);
+        assert_eq!(HostInfo::from(&node).p_vm_extensions_disabled, Some(true));
+
+        node.children[0].text = Some("0".into());
+        assert_eq!(HostInfo::from(&node).p_vm_extensions_disabled, Some(false));
+    }
+
+    #[test]
+    fn client_state_diff() {
+        let result = |name: &str, fraction_done: f64| TaskResult {
+            name: Some(name.into()),
+            project_url: Some("https://einstein.phys.uwm.edu/".into()),
+            active_task: Some(ActiveTask {
+                fraction_done: Some(fraction_done),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let project = Project {
+            master_url: Some("https://einstein.phys.uwm.edu/".into()),
+            ..Default::default()
+        };
+        let previous = ClientState {
+            projects: vec![project.clone()],
+            results: vec![result("a", 0.1), result("b", 0.5)],
+            ..Default::default()
+        };
+        let current = ClientState {
+            projects: vec![project],
+            results: vec![result("b", 0.6), result("c", 0.0)],
+            ..Default::default()
+        };
+
+        let key = |name: &str| ResultKey {
+            project_url: "https://einstein.phys.uwm.edu/".into(),
+            name: name.into(),
+        };
+        let diff = current.diff(&previous);
+        assert_eq!(diff.added_results, vec![key("c")]);
+        assert_eq!(diff.removed_results, vec![key("a")]);
+        assert_eq!(diff.changed_results, vec![key("b")]);
+        assert!(diff.added_projects.is_empty());
+        assert!(diff.removed_projects.is_empty());
+        assert!(diff.changed_projects.is_empty());
+        assert!(current.diff(&current).is_empty());
+    }
+
+    #[test]
+    fn cc_status_network_delay() {
+        let node = util::parse_node(
+            "<cc_status>\
+             <network_mode>3</network_mode>\
+             <network_mode_perm>2</network_mode_perm>\
+             <network_mode_delay>2520.000000</network_mode_delay>\
+             <task_mode_delay>0.000000</task_mode_delay>\
+             </cc_status>",
+        )
+        .unwrap();
+        let status = CcStatus::from(&node);
+        assert_eq!(status.network_mode, Some(3));
+        assert_eq!(status.network_mode_perm, Some(2));
+        assert_eq!(
+            status.network_delay_duration(),
+            Some(Duration::from_mins(42))
+        );
+        assert_eq!(status.task_delay_duration(), None);
+    }
+
+    #[test]
+    fn cc_status_network_status() {
+        let node = util::parse_node(
+            "<cc_status>\
+             <network_status>1</network_status>\
+             <ams_password_error>1</ams_password_error>\
+             </cc_status>",
+        )
+        .unwrap();
+        let status = CcStatus::from(&node);
+        assert_eq!(status.network_status, Some(NetworkStatus::WantConnection));
+        assert_eq!(status.ams_password_error, Some(true));
+
+        let node =
+            util::parse_node("<cc_status><network_status>9</network_status></cc_status>").unwrap();
+        assert_eq!(CcStatus::from(&node).network_status, None);
+    }
+
+    #[test]
+    fn notice_removal() {
+        let node = util::parse_node(
+            "<notices>\
+             <notice>\
+             <title>Einstein@Home news</title>\
+             <description><![CDATA[New <b>O3</b> search]]></description>\
+             <create_time>1700000000.0</create_time>\
+             <project_name>Einstein@Home</project_name>\
+             <category>server</category>\
+             <seqno>3</seqno>\
+             </notice>\
+             <notice><seqno>2</seqno></notice>\
+             </notices>",
+        )
+        .unwrap();
+        let notices = node.children.iter().map(Notice::from).collect::<Vec<_>>();
+
+        assert!(!notices[0].is_removal);
+        assert_eq!(notices[0].seqno, Some(3));
+        assert_eq!(
+            notices[0].description.as_deref(),
+            Some("New <b>O3</b> search")
+        );
+        assert_eq!(notices[0].category.as_deref(), Some("server"));
+
+        assert!(notices[1].is_removal);
+        assert_eq!(notices[1].seqno, Some(2));
+        assert_eq!(notices[1].title, None);
+    }
+
+    #[test]
+    fn state_display() {
+        assert_eq!(
+            [
+                ResultState::New,
+                ResultState::FilesDownloading,
+                ResultState::FilesDownloaded,
+                ResultState::ComputeError,
+                ResultState::FilesUploading,
+                ResultState::FilesUploaded,
+                ResultState::Aborted,
+                ResultState::UploadFailed,
+            ]
+            .map(|s| s.to_string()),
+            [
+                "New",
+                "Downloading",
+                "Ready to run",
+                "Computation error",
+                "Uploading",
+                "Ready to report",
+                "Aborted",
+                "Upload failed",
+            ]
+        );
+        assert_eq!(
+            [
+                Process::Uninitialized,
+                Process::Executing,
+                Process::Suspended,
+                Process::AbortPending,
+                Process::QuitPending,
+                Process::CopyPending,
+            ]
+            .map(|p| p.to_string()),
+            [
+                "Ready to start",
+                "Running",
+                "Suspended",
+                "Abort pending",
+                "Quit pending",
+                "Copy pending",
+            ]
+        );
+        assert_eq!(
+            [
+                CpuSched::Uninitialized,
+                CpuSched::Preempted,
+                CpuSched::Scheduled
+            ]
+            .map(|c| c.to_string()),
+            ["Ready to start", "Waiting to run", "Running"]
+        );
+    }
+
+    #[test]
+    fn boinc_error_codes() {
+        for (code, expected) in [
+            (-107, BoincErrorCode::Connect),
+            (-183, BoincErrorCode::ProjectDown),
+            (-205, BoincErrorCode::BadEmailAddr),
+            (-206, BoincErrorCode::BadPasswd),
+            (-208, BoincErrorCode::AcctCreationDisabled),
+            (-225, BoincErrorCode::HttpTransient),
+        ] {
+            assert_eq!(BoincErrorCode::from_i32(code), Some(expected));
+            assert_eq!(expected.code(), code);
+        }
+        assert_eq!(BoincErrorCode::BadPasswd.describe(), "bad password");
+        assert_eq!(BoincErrorCode::from_i32(0), None);
+        assert_eq!(BoincErrorCode::from_i32(-9999), None);
+
+        assert_eq!(
+            Error::Status(-183).status_code(),
+            Some(BoincErrorCode::ProjectDown)
+        );
+        assert_eq!(Error::Status(-1).status_code(), None);
+        assert_eq!(Error::Daemon("-183".into()).status_code(), None);
+    }
+
+    #[test]
+    fn run_mode_numbers() {
+        for mode in [RunMode::Always, RunMode::Auto, RunMode::Never] {
+            assert_eq!(RunMode::from_mode_num(mode.mode_num()), Some(mode));
+        }
+        assert_eq!(RunMode::Restore.mode_num(), 4);
+        assert_eq!(RunMode::from_mode_num(4), None);
+        assert_eq!(RunMode::from_mode_num(0), None);
+
+        let node = util::parse_node(
+            "<cc_status><task_mode>3</task_mode><gpu_mode>2</gpu_mode></cc_status>",
+        )
+        .unwrap();
+        let status = CcStatus::from(&node);
+        assert_eq!(status.task_run_mode(), Some(RunMode::Never));
+        assert_eq!(status.gpu_run_mode(), Some(RunMode::Auto));
+        assert_eq!(status.network_run_mode(), None);
+    }
+
+    #[test]
+    fn cc_status_suspend_reasons() {
+        let node = util::parse_node(
+            "<cc_status>\
+             <task_suspend_reason>5</task_suspend_reason>\
+             <gpu_suspend_reason>0</gpu_suspend_reason>\
+             <network_suspend_reason>4098</network_suspend_reason>\
+             </cc_status>",
+        )
+        .unwrap();
+        let status = CcStatus::from(&node);
+
+        let Some(Suspension::Reasons(task)) = status.task_suspend_reason else {
+            panic!("expected a bitmask");
+        };
+        assert_eq!(task, SuspendReason::BATTERIES | SuspendReason::USER_REQ);
+        assert!(task.contains(SuspendReason::USER_REQ));
+        assert!(!task.contains(SuspendReason::USER_ACTIVE));
+
+        assert!(!status.gpu_suspend_reason.unwrap().is_suspended());
+
+        // Codes above 4096 are values, not OS combined with other bits.
+        assert_eq!(
+            status.network_suspend_reason,
+            Some(Suspension::BatteryCharging)
+        );
+        assert!(Suspension::BatteryCharging.is_suspended());
+        assert_eq!(
+            Suspension::from_code(4096),
+            Suspension::Reasons(SuspendReason::OS)
+        );
+        assert_eq!(Suspension::from_code(4101), Suspension::Other(4101));
+    }
+
+    #[test]
+    fn project_gui_urls() {
+        let node = util::parse_node(
+            "<project>\
+             <master_url>https://einstein.phys.uwm.edu/</master_url>\
+             <gui_urls>\
+             <gui_url><name>Forum</name><description>Message boards</description>\
+             <url>https://einstein.phys.uwm.edu/forum_index.php</url></gui_url>\
+             <gui_url><name>Your account</name><description>View your account</description>\
+             <url>https://einstein.phys.uwm.edu/home.php</url></gui_url>\
+             </gui_urls>\
+             </project>",
+        )
+        .unwrap();
+        let project = Project::from(&node);
+        assert_eq!(
+            project.gui_urls,
+            vec![
+                GuiUrl {
+                    name: Some("Forum".into()),
+                    description: Some("Message boards".into()),
+                    url: Some("https://einstein.phys.uwm.edu/forum_index.php".into()),
+                },
+                GuiUrl {
+                    name: Some("Your account".into()),
+                    description: Some("View your account".into()),
+                    url: Some("https://einstein.phys.uwm.edu/home.php".into()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn partial_json_uses_defaults() {
+        let result: TaskResult =
+            serde_json::from_str(r#"{"name": "wu_1_0", "active_task": {"slot": 2}}"#).unwrap();
+        assert_eq!(result.name.as_deref(), Some("wu_1_0"));
+        assert_eq!(result.active_task.and_then(|t| t.slot), Some(2));
+        assert_eq!(result.state, None);
+    }
+
+    #[test]
+    fn disk_usage_allowed() {
+        let node = util::parse_node(
+            "<disk_usage_summary>\
+             <project><master_url>https://einstein.phys.uwm.edu/</master_url>\
+             <disk_usage>600000000</disk_usage></project>\
+             <project><master_url>https://www.worldcommunitygrid.org/</master_url>\
+             <disk_usage>300000000</disk_usage></project>\
+             <d_total>500000000000</d_total>\
+             <d_free>200000000000</d_free>\
+             <d_boinc>100000000</d_boinc>\
+             <d_allowed>1000000000</d_allowed>\
+             </disk_usage_summary>",
+        )
+        .unwrap();
+        let mut usage = DiskUsage::from(&node);
+        assert_eq!(usage.projects.len(), 2);
+        assert_eq!(usage.pct_used(), Some(100.0));
+        assert!(!usage.is_over_allowed());
+
+        usage.d_boinc = Some(200_000_000.0);
+        assert!(usage.is_over_allowed());
+    }
+
+    #[test]
+    fn file_transfer_backoff() {
+        let node = util::parse_node(
+            "<file_transfer>\
+             <project_url>https://einstein.phys.uwm.edu/</project_url>\
+             <name>h1_0001_result_0_0</name>\
+             <nbytes>1048576.000000</nbytes>\
+             <status>0</status>\
+             <project_backoff>120.5</project_backoff>\
+             <persistent_file_xfer>\
+             <num_retries>3</num_retries>\
+             <first_request_time>1700000000.0</first_request_time>\
+             <next_request_time>1700000400.0</next_request_time>\
+             <time_so_far>35.2</time_so_far>\
+             <last_bytes_xferred>524288.0</last_bytes_xferred>\
+             <is_upload>1</is_upload>\
+             </persistent_file_xfer>\
+             </file_transfer>",
+        )
+        .unwrap();
+        let xfer = FileTransfer::from(&node);
+        let pfx = xfer.persistent_file_xfer.as_ref().unwrap();
+        assert_eq!(pfx.last_bytes_xferred, Some(524_288.0));
+        assert_eq!(pfx.is_upload, Some(true));
+        assert_eq!(xfer.project_backoff, Some(120.5));
+        assert_eq!(
+            pfx.next_retry_in(1_700_000_220.0),
+            Some(Duration::from_mins(3))
+        );
+        assert_eq!(
+            xfer.transfer_status(1_700_000_220.0),
+            TransferStatus::RetryScheduled
+        );
+        assert_eq!(
+            xfer.transfer_status(1_700_000_500.0),
+            TransferStatus::InProgress
+        );
+        assert_eq!(
+            FileTransfer {
+                status: Some(-161),
+                ..xfer
+            }
+            .transfer_status(0.0),
+            TransferStatus::Error(-161)
+        );
+    }
+
+    #[test]
+    fn project_info_image_url() {
+        let mut info = ProjectInfo {
+            url: Some("https://einstein.phys.uwm.edu/".into()),
+            image: Some("img/logo.png".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            info.image_url().as_deref(),
+            Some("https://einstein.phys.uwm.edu/img/logo.png")
+        );
+
+        info.url = Some("https://boinc.bakerlab.org/rosetta/".into());
+        info.image = Some("/images/rosetta.png".into());
+        assert_eq!(
+            info.image_url().as_deref(),
+            Some("https://boinc.bakerlab.org/images/rosetta.png")
+        );
+
+        info.image = Some("https://cdn.example.com/logo.png".into());
+        assert_eq!(
+            info.image_url().as_deref(),
+            Some("https://cdn.example.com/logo.png")
+        );
+    }
+
+    #[test]
+    fn project_info_platforms() {
+        let node = util::parse_node(
+            "<project><platforms>\
+             <name>windows_x86_64</name><name>x86_64-pc-linux-gnu</name>\
+             </platforms></project>",
+        )
+        .unwrap();
+        assert_eq!(
+            ProjectInfo::from(&node).platforms,
+            Some(vec![
+                Platform::from("windows_x86_64"),
+                Platform::from("x86_64-pc-linux-gnu")
+            ])
+        );
+    }
+
+    #[test]
+    fn project_rpc_timing() {
+        let node = util::parse_node(
+            "<project>\
+             <master_url>https://einstein.phys.uwm.edu/</master_url>\
+             <last_rpc_time>1700000000.000000</last_rpc_time>\
+             <min_rpc_time>1700003600.000000</min_rpc_time>\
+             <download_backoff>1700000600.000000</download_backoff>\
+             <upload_backoff>0.000000</upload_backoff>\
+             <project_files_downloaded_time>1699990000.000000</project_files_downloaded_time>\
+             </project>",
+        )
+        .unwrap();
+        let project = Project::from(&node);
+        assert_eq!(project.last_rpc_time, Some(1_700_000_000.0));
+        assert_eq!(project.project_files_downloaded_time, Some(1_699_990_000.0));
+        assert_eq!(project.download_backoff, Some(1_700_000_600.0));
+        assert_eq!(project.upload_backoff, Some(0.0));
+        assert!(project.in_backoff(1_700_000_100.0));
+        assert!(!project.in_backoff(1_700_003_601.0));
+    }
+
+    #[test]
+    fn account_manager_info_flags() {
+        let node = util::parse_node(
+            "<acct_mgr_info>\
+             <acct_mgr_url>https://bam.boincstats.com/</acct_mgr_url>\
+             <have_credentials>1</have_credentials>\
+             <cookie_required>0</cookie_required>\
+             </acct_mgr_info>",
+        )
+        .unwrap();
+        let info = AccountManagerInfo::from(&node);
+        assert_eq!(info.have_credentials, Some(true));
+        assert_eq!(info.cookie_required, Some(false));
+
+        // Older clients send the bare tag.
+        let node = util::parse_node("<acct_mgr_info><have_credentials/></acct_mgr_info>").unwrap();
+        let info = AccountManagerInfo::from(&node);
+        assert_eq!(info.have_credentials, Some(true));
+        assert_eq!(info.cookie_required, None);
+    }
+
+    #[test]
+    fn project_time_since_last_rpc() {
+        let project = Project {
+            last_rpc_time: Some(1_700_000_000.0),
+            ..Project::default()
+        };
+        assert_eq!(
+            project.time_since_last_rpc(1_700_086_400.0),
+            Some(Duration::from_hours(24))
+        );
+        // A clock behind the last RPC doesn't yield a negative age.
+        assert_eq!(
+            project.time_since_last_rpc(1_699_999_000.0),
+            Some(Duration::ZERO)
+        );
+        let never = Project {
+            last_rpc_time: Some(0.0),
+            ..Project::default()
+        };
+        assert_eq!(never.time_since_last_rpc(1_700_000_000.0), None);
+        assert_eq!(
+            Project::default().time_since_last_rpc(1_700_000_000.0),
+            None
+        );
+    }
+
+    #[test]
+    fn task_result_from_xml() {
+        let node = util::parse_node(
+            "<result>\
+             <name> h1_0_0 </name>\
+             <wu_name>h1_0</wu_name>\
+             <platform>x86_64-pc-linux-gnu</platform>\
+             <version_num>104</version_num>\
+             <plan_class>GW-opencl-nvidia-2</plan_class>\
+             <project_url>https://einstein.phys.uwm.edu/</project_url>\
+             <final_cpu_time>0.000000</final_cpu_time>\
+             <exit_status>0</exit_status>\
+             <state>2</state>\
+             <report_deadline>1700604800.000000</report_deadline>\
+             <received_time>1700000000.000000</received_time>\
+             <resources>0.5 NVIDIA GPU</resources>\
+             <active_task>\
+             <active_task_state>1</active_task_state>\
+             <app_version_num>104</app_version_num>\
+             <slot>3</slot>\
+             <pid>4242</pid>\
+             <scheduler_state>2</scheduler_state>\
+             <fraction_done>0.250000</fraction_done>\
+             <elapsed_time>140.000000</elapsed_time>\
+             <gpu_device_num>1</gpu_device_num>\
+             </active_task>\
+             </result>",
+        )
+        .unwrap();
+        assert_eq!(
+            TaskResult::from(&node),
+            TaskResult {
+                name: Some("h1_0_0".into()),
+                wu_name: Some("h1_0".into()),
+                platform: Some("x86_64-pc-linux-gnu".into()),
+                version_num: Some(104),
+                plan_class: Some("GW-opencl-nvidia-2".into()),
+                project_url: Some("https://einstein.phys.uwm.edu/".into()),
+                final_cpu_time: Some(0.0),
+                exit_status: Some(0),
+                state: Some(2),
+                report_deadline: Some(1_700_604_800.0),
+                received_time: Some(1_700_000_000.0),
+                resources: Some("0.5 NVIDIA GPU".into()),
+                active_task: Some(ActiveTask {
+                    active_task_state: Some("1".into()),
+                    app_version_num: Some("104".into()),
+                    slot: Some(3),
+                    pid: Some(4242),
+                    scheduler_state: Some("2".into()),
+                    fraction_done: Some(0.25),
+                    elapsed_time: Some(140.0),
+                    gpu_device_num: Some(1),
+                    ..ActiveTask::default()
+                }),
+                ..TaskResult::default()
+            }
+        );
+    }
+
+    #[test]
+    fn result_deadline() {
+        let result = TaskResult {
+            report_deadline: Some(1_700_086_400.0),
+            ..TaskResult::default()
+        };
+        assert_eq!(result.time_to_deadline(1_700_000_000.0), Some(86_400.0));
+        assert!(!result.is_past_deadline(1_700_000_000.0));
+        assert!(result.is_past_deadline(1_700_086_401.0));
+
+        let result = TaskResult::default();
+        assert_eq!(result.time_to_deadline(1_700_000_000.0), None);
+        assert!(!result.is_past_deadline(1_700_000_000.0));
+    }
+
+    #[test]
+    fn result_age() {
+        let result = TaskResult {
+            received_time: Some(1_699_996_400.0),
+            report_deadline: Some(1_700_086_400.0),
+            ..TaskResult::default()
+        };
+        assert_eq!(result.age(1_700_000_000.0), Some(Duration::from_hours(1)));
+        assert_eq!(
+            result.time_until_deadline(1_700_000_000.0),
+            Some(Duration::from_hours(24))
+        );
+        assert_eq!(
+            result.time_until_deadline(1_700_090_000.0),
+            Some(Duration::ZERO)
+        );
+
+        let result = TaskResult::default();
+        assert_eq!(result.age(1_700_000_000.0), None);
+        assert_eq!(result.time_until_deadline(1_700_000_000.0), None);
+    }
+
+    #[test]
+    fn host_info_identity() {
+        let node = util::parse_node(
+            "<host_info>\
+             <serialnum>[BOINC|8.0.4][CUDA|NVIDIA GeForce RTX 3060|1|12288MB|55123|300][vbox|7.0.12|1|1]</serialnum>\
+             <product_name> Dell Inc. OptiPlex 7090 </product_name>\
+             <os_name>Microsoft Windows 11</os_name>\
+             <os_version><![CDATA[Professional x64 Edition, (10.00.22631.00)]]></os_version>\
+             </host_info>",
+        )
+        .unwrap();
+        let host = HostInfo::from(&node);
+        assert_eq!(
+            host.serialnum.as_deref(),
+            Some(
+                "[BOINC|8.0.4][CUDA|NVIDIA GeForce RTX 3060|1|12288MB|55123|300][vbox|7.0.12|1|1]"
+            )
+        );
+        assert_eq!(
+            host.product_name.as_deref(),
+            Some("Dell Inc. OptiPlex 7090")
+        );
+        assert_eq!(
+            host.os_version.as_deref(),
+            Some("Professional x64 Edition, (10.00.22631.00)")
+        );
+
+        let node =
+            util::parse_node("<host_info><serialnum></serialnum><product_name/></host_info>")
+                .unwrap();
+        let host = HostInfo::from(&node);
+        assert_eq!(host.serialnum, None);
+        assert_eq!(host.product_name, None);
+    }
+
+    #[test]
+    fn proxy_info_debug_hides_passwords() {
+        let node = util::parse_node(
+            "<proxy_info>\
+             <use_http_proxy/>\
+             <use_http_auth/>\
+             <http_server_name>proxy.example.com</http_server_name>\
+             <http_server_port>3128</http_server_port>\
+             <http_user_name>alice</http_user_name>\
+             <http_user_passwd>hunter2</http_user_passwd>\
+             <socks5_user_passwd>s3cret</socks5_user_passwd>\
+             </proxy_info>",
+        )
+        .unwrap();
+        let proxy = ProxyInfo::from(&node);
+        assert_eq!(proxy.use_http_proxy, Some(true));
+        assert_eq!(proxy.http_server_port, Some(3128));
+        assert_eq!(proxy.http_user_passwd.as_deref(), Some("hunter2"));
+
+        let debug = format!("{proxy:?}");
+        assert!(debug.contains("alice"));
+        assert!(debug.contains("***"));
+        assert!(!debug.contains("hunter2"));
+        assert!(!debug.contains("s3cret"));
+    }
+
+    #[test]
+    fn result_resources_and_scheduler_wait() {
+        let node = util::parse_node(
+            "<result>\
+             <name>wu_1_0</name>\
+             <resources>1 CPU + 1 NVIDIA GPU</resources>\
+             <active_task>\
+             <active_task_state>0</active_task_state>\
+             <scheduler_wait/>\
+             <scheduler_wait_reason>Waiting for GPU memory</scheduler_wait_reason>\
+             </active_task>\
+             </result>",
+        )
+        .unwrap();
+        let result = TaskResult::from(&node);
+        assert_eq!(result.resources.as_deref(), Some("1 CPU + 1 NVIDIA GPU"));
+        assert_eq!(result.scheduler_wait, Some(true));
+        assert_eq!(
+            result.scheduler_wait_reason.as_deref(),
+            Some("Waiting for GPU memory")
+        );
+        assert!(result.active_task.is_some());
+    }
+
+    #[test]
+    fn message_rpc_timing() {
+        let message = |body: &str| Message {
+            project_name: Some("Einstein@Home".into()),
+            body: Some(body.into()),
+            ..Message::default()
+        };
+        assert_eq!(
+            message("Project requested delay of 61 seconds").parse_rpc_timing(),
+            Some(RpcTiming::RequestedDelay(Duration::from_secs(61)))
+        );
+        assert_eq!(
+            message("[sched_op] Deferring communication for 00:01:34").parse_rpc_timing(),
+            Some(RpcTiming::Deferral(Duration::from_secs(94)))
+        );
+        assert_eq!(
+            message("[sched_op] Deferring communication for 1 days 02:00:00").parse_rpc_timing(),
+            Some(RpcTiming::Deferral(Duration::from_hours(26)))
+        );
+        assert_eq!(
+            message("[http] HTTP operation completed in 2.5 sec").parse_rpc_timing(),
+            Some(RpcTiming::Elapsed(Duration::from_millis(2500)))
+        );
+        assert_eq!(
+            message("Scheduler request completed: got 2 new tasks").parse_rpc_timing(),
+            None
+        );
+    }
+
+    #[test]
+    fn disk_usage_fraction() {
+        let usage = DiskUsage {
+            d_boinc: Some(1_000.0),
+            d_allowed: Some(10_000.0),
+            projects: vec![ProjectDiskUsage {
+                disk_usage: Some(4_000.0),
+                ..ProjectDiskUsage::default()
+            }],
+            ..DiskUsage::default()
+        };
+        assert_eq!(usage.usage_fraction(), Some(0.5));
+        assert!(!usage.is_near_limit(0.9));
+
+        let full = DiskUsage {
+            d_boinc: Some(10_000.0),
+            ..usage.clone()
+        };
+        assert!(full.usage_fraction().unwrap() > 1.0);
+        assert!(full.is_near_limit(0.9));
+        assert!(full.is_near_limit(1.0));
+
+        let unlimited = DiskUsage {
+            d_allowed: Some(0.0),
+            ..usage
+        };
+        assert_eq!(unlimited.usage_fraction(), None);
+        assert!(!unlimited.is_near_limit(0.9));
+    }
+
+    #[test]
+    fn client_state_platforms() {
+        let node = util::parse_node(
+            "<client_state>\
+             <platform_name>x86_64-pc-linux-gnu</platform_name>\
+             <platform>x86_64-pc-linux-gnu</platform>\
+             <platform>i686-pc-linux-gnu</platform>\
+             <platform>arm-unknown-linux-gnueabihf</platform>\
+             <app_version><platform>windows_x86_64</platform></app_version>\
+             </client_state>",
+        )
+        .unwrap();
+        assert_eq!(
+            ClientState::from(&node)
+                .platforms
+                .iter()
+                .map(Platform::as_str)
+                .collect::<Vec<_>>(),
+            vec![
+                "x86_64-pc-linux-gnu",
+                "i686-pc-linux-gnu",
+                "arm-unknown-linux-gnueabihf"
+            ]
+        );
+    }
+
+    #[test]
+    fn client_state_app_versions() {
+        let node = util::parse_node(
+            "<client_state>\
+             <project><master_url>https://einstein.phys.uwm.edu/</master_url></project>\
+             <app_version>\
+             <app_name>einstein_O3AS</app_name>\
+             <version_num>104</version_num>\
+             <platform>x86_64-pc-linux-gnu</platform>\
+             <avg_ncpus>1.000000</avg_ncpus>\
+             <flops>180000000000.000000</flops>\
+             <plan_class>GW-opencl-nvidia-2</plan_class>\
+             <coproc><type>NVIDIA</type><count>0.500000</count></coproc>\
+             </app_version>\
+             <app_version>\
+             <app_name>einstein_O3AS</app_name>\
+             <version_num>104</version_num>\
+             <platform>x86_64-pc-linux-gnu</platform>\
+             <avg_ncpus>1.000000</avg_ncpus>\
+             </app_version>\
+             <result>\
+             <name>h1_0_0</name>\
+             <project_url>https://einstein.phys.uwm.edu/</project_url>\
+             <version_num>104</version_num>\
+             <plan_class>GW-opencl-nvidia-2</plan_class>\
+             </result>\
+             </client_state>",
+        )
+        .unwrap();
+        let state = ClientState::from(&node);
+        assert_eq!(state.app_versions.len(), 2);
+        assert_eq!(
+            state.app_versions[0].project_url.as_deref(),
+            Some("https://einstein.phys.uwm.edu/")
+        );
+
+        let version = state.app_version_for(&state.results[0]).unwrap();
+        assert_eq!(version.gpu_type.as_deref(), Some("NVIDIA"));
+        assert_eq!(version.gpu_usage, Some(0.5));
+        assert_eq!(version.flops, Some(180_000_000_000.0));
+
+        let cpu_result = TaskResult {
+            plan_class: None,
+            ..state.results[0].clone()
+        };
+        let version = state.app_version_for(&cpu_result).unwrap();
+        assert_eq!(version.gpu_type, None);
+        assert_eq!(version.avg_ncpus, Some(1.0));
+    }
+
+    #[test]
+    fn client_state_workunits() {
+        let node = util::parse_node(
+            "<client_state>\
+             <project><master_url>https://einstein.phys.uwm.edu/</master_url></project>\
+             <workunit>\
+             <name>h1_0</name>\
+             <app_name>einstein_O3AS</app_name>\
+             <version_num>104</version_num>\
+             <rsc_fpops_est>144000000000000.000000</rsc_fpops_est>\
+             <rsc_fpops_bound>2880000000000000.000000</rsc_fpops_bound>\
+             <rsc_memory_bound>1000000000.000000</rsc_memory_bound>\
+             <rsc_disk_bound>20000000.000000</rsc_disk_bound>\
+             </workunit>\
+             <project><master_url>https://boinc.bakerlab.org/rosetta/</master_url></project>\
+             <workunit><name>h1_0</name><rsc_fpops_est>1.0</rsc_fpops_est></workunit>\
+             <result>\
+             <name>h1_0_0</name>\
+             <wu_name>h1_0</wu_name>\
+             <project_url>https://einstein.phys.uwm.edu/</project_url>\
+             </result>\
+             </client_state>",
+        )
+        .unwrap();
+        let state = ClientState::from(&node);
+        assert_eq!(state.workunits.len(), 2);
+
+        let workunit = state.workunit_for(&state.results[0]).unwrap();
+        assert_eq!(workunit.app_name.as_deref(), Some("einstein_O3AS"));
+        assert_eq!(workunit.rsc_fpops_est, Some(144_000_000_000_000.0));
+        assert_eq!(workunit.rsc_fpops_bound, Some(2_880_000_000_000_000.0));
+        assert_eq!(workunit.rsc_memory_bound, Some(1_000_000_000.0));
+        assert_eq!(workunit.rsc_disk_bound, Some(20_000_000.0));
+
+        let other = TaskResult {
+            project_url: Some("https://boinc.bakerlab.org/rosetta/".into()),
+            ..state.results[0].clone()
+        };
+        assert_eq!(
+            state.workunit_for(&other).and_then(|w| w.rsc_fpops_est),
+            Some(1.0)
+        );
+        assert!(state.workunit_for(&TaskResult::default()).is_none());
+    }
+
+    #[test]
+    fn active_task_slot_dir() {
+        let task = ActiveTask {
+            slot: Some(3),
+            ..ActiveTask::default()
+        };
+        assert_eq!(
+            task.slot_dir(Path::new("/var/lib/boinc-client")),
+            Some(PathBuf::from("/var/lib/boinc-client/slots/3"))
+        );
+        assert_eq!(
+            ActiveTask::default().slot_dir(Path::new("/var/lib/boinc-client")),
+            None
+        );
+    }
+
+    #[test]
+    fn active_task_gpu() {
+        let node = util::parse_node(
+            "<active_task>\
+             <active_task_state>1</active_task_state>\
+             <app_version_num>104</app_version_num>\
+             <app_version>1.04 (GW-opencl-nvidia-2)</app_version>\
+             <ncpus>1.000000</ncpus>\
+             <gpu_usage>0.500000</gpu_usage>\
+             <gpu_device_num>0</gpu_device_num>\
+             </active_task>",
+        )
+        .unwrap();
+        let task = ActiveTask::from(&node);
+        assert_eq!(
+            task.app_version.as_deref(),
+            Some("1.04 (GW-opencl-nvidia-2)")
+        );
+        assert_eq!(task.ncpus, Some(1.0));
+        assert_eq!(task.gpu_usage, Some(0.5));
+        assert_eq!(task.gpu_device_num, Some(0));
+    }
+
+    #[test]
+    fn host_info_byte_quantities() {
+        let host = HostInfo {
+            m_nbytes: Some(16_106_127_360.0),
+            d_free: Some(-1.0),
+            ..HostInfo::default()
+        };
+        assert_eq!(host.memory_bytes(), Some(16_106_127_360));
+        assert_eq!(host.disk_free_bytes(), None);
+        assert_eq!(host.swap_bytes(), None);
+    }
+
+    #[cfg(feature = "humansize")]
+    #[test]
+    fn byte_size_display() {
+        assert_eq!(super::ByteSize(16_106_127_360).to_string(), "15.0 GiB");
+        assert_eq!(super::ByteSize(512).to_string(), "512 B");
+        assert_eq!(super::ByteSize(1536).to_string(), "1.5 KiB");
+    }
+
+    #[test]
+    fn global_prefs_round_trip() {
+        let prefs = GlobalPreferences {
+            run_on_batteries: Some(false),
+            cpu_usage_limit: Some(75.5),
+            daily_xfer_period_days: Some(2),
+            ..GlobalPreferences::default()
+        };
+        let node = treexml::Element::from(&prefs);
+        assert_eq!(node.name, "global_preferences");
+        assert_eq!(node.children.len(), 3);
+        assert_eq!(GlobalPreferences::from(&node), prefs);
+    }
+
+    #[test]
+    fn global_prefs_validate() {
+        let prefs = GlobalPreferences {
+            cpu_usage_limit: Some(80.0),
+            max_ncpus_pct: Some(100.0),
+            work_buf_min_days: Some(0.1),
+            end_hour: Some(24.0),
+            ..GlobalPreferences::default()
+        };
+        assert_eq!(prefs.validate(), Ok(()));
+
+        let prefs = GlobalPreferences {
+            cpu_usage_limit: Some(150.0),
+            ..prefs
+        };
+        assert!(matches!(
+            prefs.validate(),
+            Err(Error::DataParse(e)) if e.starts_with("cpu_usage_limit")
+        ));
+
+        let prefs = GlobalPreferences {
+            cpu_usage_limit: None,
+            work_buf_additional_days: Some(-1.0),
+            ..prefs
+        };
+        assert!(matches!(
+            prefs.validate(),
+            Err(Error::DataParse(e)) if e.starts_with("work_buf_additional_days")
+        ));
+    }
+
+    #[test]
+    fn platform_parts() {
+        let linux = Platform::from("x86_64-pc-linux-gnu");
+        assert_eq!(linux.arch(), "x86_64");
+        assert_eq!(linux.os(), "linux-gnu");
+
+        let windows = Platform::from("windows_x86_64");
+        assert_eq!(windows.arch(), "x86_64");
+        assert_eq!(windows.os(), "windows");
+        assert_eq!(
+            serde_json::to_value(&windows).unwrap(),
+            serde_json::json!("windows_x86_64")
+        );
+
+        assert!(is_gpu_plan_class("cuda"));
+        assert!(is_gpu_plan_class("GW-opencl-nvidia-2"));
+        assert!(is_gpu_plan_class("ati13ati"));
+        assert!(is_gpu_plan_class("intel_gpu"));
+        assert!(!is_gpu_plan_class("vbox64"));
+        assert!(!is_gpu_plan_class("sse2_optimization"));
+        assert!(!is_gpu_plan_class(""));
+    }
+
+    #[test]
+    fn file_transfer_speed_and_eta() {
+        let transfer = |time_so_far: f64, last_bytes_xferred: f64| FileTransfer {
+            nbytes: Some(10_000_000.0),
+            persistent_file_xfer: Some(PersistentFileXfer {
+                time_so_far: Some(time_so_far),
+                last_bytes_xferred: Some(last_bytes_xferred),
+                ..PersistentFileXfer::default()
+            }),
+            ..FileTransfer::default()
+        };
+
+        let active = transfer(20.0, 2_000_000.0);
+        assert_eq!(active.speed_bps(), Some(100_000.0));
+        assert_eq!(active.eta(), Some(80.0));
+
+        let starting = transfer(0.0, 0.0);
+        assert_eq!(starting.speed_bps(), None);
+        assert_eq!(starting.eta(), None);
+
+        let stalled = transfer(20.0, 0.0);
+        assert_eq!(stalled.speed_bps(), Some(0.0));
+        assert_eq!(stalled.eta(), None);
+    }
+
+    #[test]
+    fn project_info_keywords() {
+        let node = util::parse_node(
+            "<project>\
+             <name>Rosetta@home</name>\
+             <keywords>\
+             <sci>Biology</sci>\
+             <sci>Protein folding</sci>\
+             <loc>United States</loc>\
+             <keyword>Medicine</keyword>\
+             </keywords>\
+             </project>",
+        )
+        .unwrap();
+        let info = ProjectInfo::from(&node);
+        assert_eq!(info.sci_keywords, vec!["Biology", "Protein folding"]);
+        assert_eq!(info.loc_keywords, vec!["United States"]);
+        assert_eq!(info.keywords.len(), 4);
+        assert!(info.keywords.contains(&"Medicine".to_string()));
+
+        let node = util::parse_node("<project><keywords>6 13 37</keywords></project>").unwrap();
+        assert_eq!(ProjectInfo::from(&node).keywords, vec!["6", "13", "37"]);
+    }
+}
@@ -4,22 +4,41 @@ use bytes::BytesMut;
 use crypto::digest::Digest;
 use encoding::{all::ISO_8859_1, DecoderTrap, EncoderTrap, Encoding};
 use futures::{SinkExt, TryStreamExt};
+use std::time::Duration;
 use tokio::{
     io::{AsyncRead, AsyncWrite},
     net::TcpStream,
 };
-use tokio_util::codec::{Decoder, Encoder, Framed};
+use tokio_util::codec::{Decoder, Encoder, Framed, FramedParts};
 use tracing::trace;
 
 use crate::{errors::Error, models, util};
 
-fn compute_nonce_hash(pass: &str, nonce: &str) -> String {
-    let mut digest = crypto::md5::Md5::new();
-    digest.input_str(&format!("{nonce}{pass}"));
-    digest.result_str()
+/// Computes the `nonce_hash` answering the daemon's `<nonce>` challenge.
+pub trait AuthHasher {
+    fn nonce_hash(&self, nonce: &str, password: &str) -> String;
 }
 
-const TERMCHAR: u8 = 3;
+/// The hash BOINC uses: hex MD5 of the nonce followed by the password.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Md5Hasher;
+
+impl AuthHasher for Md5Hasher {
+    fn nonce_hash(&self, nonce: &str, password: &str) -> String {
+        let mut digest = crypto::md5::Md5::new();
+        digest.input_str(&format!("{nonce}{password}"));
+        digest.result_str()
+    }
+}
+
+/// Byte terminating every request and reply on the wire.
+pub const TERMCHAR: u8 = 3;
+
+/// Root tag wrapping requests sent by a client.
+pub const REQUEST_ROOT: &str = "boinc_gui_rpc_request";
+
+/// Root tag wrapping replies sent by a daemon.
+pub const REPLY_ROOT: &str = "boinc_gui_rpc_reply";
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum CodecMode {
@@ -27,9 +46,58 @@ pub enum CodecMode {
     Server,
 }
 
+impl CodecMode {
+    /// Root tag of the messages this side sends.
+    #[must_use]
+    pub const fn outgoing_root(self) -> &'static str {
+        match self {
+            Self::Client => REQUEST_ROOT,
+            Self::Server => REPLY_ROOT,
+        }
+    }
+
+    /// Root tag of the messages this side expects to receive.
+    #[must_use]
+    pub const fn incoming_root(self) -> &'static str {
+        match self {
+            Self::Client => REPLY_ROOT,
+            Self::Server => REQUEST_ROOT,
+        }
+    }
+}
+
+/// Wraps `children` in the root element that `mode` sends, producing the
+/// document that goes on the wire before the [`TERMCHAR`] terminator.
+#[must_use]
+pub fn wrap_message(mode: CodecMode, children: Vec<treexml::Element>) -> treexml::Element {
+    let mut out = treexml::Element::new(mode.outgoing_root());
+    out.children = children;
+    out
+}
+
+/// Default cap on the size of a single incoming message; see
+/// [`BoincCodec::with_max_reply_size`].
+pub const DEFAULT_MAX_REPLY_SIZE: usize = 64 * 1024 * 1024;
+
+/// The parts of a `get_state` reply that [`models::ClientState`] reads. The
+/// client decodes only these, as the rest (file lists, app details, time
+/// and network statistics) can make up most of the reply.
+pub const GET_STATE_SECTIONS: &[&str] = &[
+    "host_info",
+    "project",
+    "app_version",
+    "workunit",
+    "result",
+    "platform",
+];
+
 pub struct BoincCodec {
     mode: CodecMode,
     next_index: usize,
+    max_reply_size: usize,
+    sections: Vec<String>,
+    /// Sections to decode from the reply to the request last encoded.
+    reply_sections: &'static [&'static str],
 }
 
 impl BoincCodec {
@@ -38,8 +106,32 @@ impl BoincCodec {
         Self {
             mode,
             next_index: 0,
+            max_reply_size: DEFAULT_MAX_REPLY_SIZE,
+            sections: Vec::new(),
+            reply_sections: &[],
         }
     }
+
+    /// Fails decoding once `max` bytes have arrived without a terminator, so
+    /// a misbehaving peer cannot make the buffer grow without bound.
+    #[must_use]
+    pub const fn with_max_reply_size(mut self, max: usize) -> Self {
+        self.max_reply_size = max;
+        self
+    }
+
+    /// Decodes only the elements named in `sections`, e.g. `["project",
+    /// "result"]` for a `get_state` reply, without building the rest of the
+    /// document. Elements enclosing a section are kept, as are children of
+    /// the root such as `<error>`.
+    ///
+    /// Without this, a client codec decodes replies to `get_state` with
+    /// [`GET_STATE_SECTIONS`] and every other reply in full.
+    #[must_use]
+    pub fn with_sections(mut self, sections: &[&str]) -> Self {
+        self.sections = sections.iter().map(|&s| s.to_owned()).collect();
+        self
+    }
 }
 
 impl Decoder for BoincCodec {
@@ -55,6 +147,12 @@ impl Decoder for BoincCodec {
         {
             let newline_index = offset + self.next_index;
             self.next_index = 0;
+            if newline_index > self.max_reply_size {
+                return Err(Error::DataParse(format!(
+                    "Reply too large: {newline_index} bytes, limit is {}",
+                    self.max_reply_size
+                )));
+            }
             let line = src.split_to(newline_index + 1);
             let line = &line[..line.len() - 1];
             let line = ISO_8859_1
@@ -63,22 +161,32 @@ impl Decoder for BoincCodec {
 
             trace!("Received data: {}", line);
 
-            let line = line.trim_start_matches("<?xml version=\"1.0\" encoding=\"ISO-8859-1\" ?>");
-            let root_node = util::parse_node(line)?;
-
-            let expected_root = match self.mode {
-                CodecMode::Client => "boinc_gui_rpc_reply",
-                CodecMode::Server => "boinc_gui_rpc_request",
+            let line = util::strip_prolog(&line);
+            let root_node = if !self.sections.is_empty() {
+                let sections = self.sections.iter().map(String::as_str).collect::<Vec<_>>();
+                util::parse_sections(line, &sections)?
+            } else if !self.reply_sections.is_empty() {
+                util::parse_sections(line, self.reply_sections)?
+            } else {
+                util::parse_node(line)?
             };
 
+            let expected_root = self.mode.incoming_root();
+
             if root_node.name != expected_root {
+                // Usually the wrong port, or a proxy answering with an error page.
                 return Err(Error::DataParse(format!(
-                    "Invalid root: {}. Expected: {}",
-                    root_node.name, expected_root
+                    "unexpected reply root: <{}>, expected <{expected_root}>",
+                    root_node.name
                 )));
             }
 
             Ok(Some(root_node.children))
+        } else if read_to > self.max_reply_size {
+            Err(Error::DataParse(format!(
+                "Reply too large: no terminator in {read_to} bytes, limit is {}",
+                self.max_reply_size
+            )))
         } else {
             self.next_index = read_to;
             Ok(None)
@@ -94,39 +202,143 @@ impl Encoder<Vec<treexml::Element>> for BoincCodec {
         item: Vec<treexml::Element>,
         dst: &mut BytesMut,
     ) -> Result<(), Self::Error> {
-        let mut out = treexml::Element::new(match self.mode {
-            CodecMode::Client => "boinc_gui_rpc_request",
-            CodecMode::Server => "boinc_gui_rpc_reply",
-        });
-        out.children = item;
-
-        let data = format!("{out}")
-            .replace("<?xml version='1.0'?>", "")
-            .replace(" />", "/>");
+        if self.mode == CodecMode::Client {
+            self.reply_sections = match item.first().map(|e| &*e.name) {
+                Some("get_state") => GET_STATE_SECTIONS,
+                _ => &[],
+            };
+        }
+        let data = util::serialize_node(&wrap_message(self.mode, item));
 
         trace!("Sending data: {}", data);
         dst.extend_from_slice(
             &ISO_8859_1
                 .encode(&data, EncoderTrap::Strict)
-                .expect("Our data should always be correct"),
+                .map_err(|e| Error::DataParse(format!("Cannot encode request: {e}")))?,
         );
         dst.extend_from_slice(&[TERMCHAR]);
         Ok(())
     }
 }
 
+/// An authenticated connection to a BOINC daemon.
+///
+/// Replies are buffered until their terminator arrives, with the buffer
+/// growing as needed up to the reply size limit.
 pub struct DaemonStream<Io> {
     conn: Framed<Io, BoincCodec>,
 }
 
+impl<Io> DaemonStream<Io> {
+    /// Terminator byte ending each request; see [`TERMCHAR`].
+    pub const REQUEST_END: u8 = TERMCHAR;
+
+    /// Swaps the underlying stream for `f(stream)`, keeping buffered data.
+    pub(crate) fn map_io<T>(self, f: impl FnOnce(Io) -> T) -> DaemonStream<T> {
+        let parts = self.conn.into_parts();
+        let mut mapped = FramedParts::new(f(parts.io), parts.codec);
+        mapped.read_buf = parts.read_buf;
+        mapped.write_buf = parts.write_buf;
+        DaemonStream {
+            conn: Framed::from_parts(mapped),
+        }
+    }
+
+    /// Caps the size of a single reply, [`DEFAULT_MAX_REPLY_SIZE`] by default.
+    /// A larger reply fails the query with [`Error::DataParse`].
+    pub fn set_max_reply_size(&mut self, max: usize) {
+        self.conn.codec_mut().max_reply_size = max;
+    }
+}
+
+#[allow(clippy::missing_errors_doc)]
 impl DaemonStream<TcpStream> {
     pub async fn connect(host: String, password: Option<String>) -> Result<Self, Error> {
         Self::authenticate(TcpStream::connect(host).await?, password).await
     }
+
+    /// Like [`Self::connect`], answering the nonce challenge with `hasher`
+    /// instead of [`Md5Hasher`].
+    pub async fn connect_with_hasher<H: AuthHasher>(
+        host: String,
+        password: Option<String>,
+        hasher: H,
+    ) -> Result<Self, Error> {
+        Self::authenticate_with(TcpStream::connect(host).await?, password, hasher).await
+    }
 }
 
+#[cfg(feature = "tls")]
+#[allow(clippy::missing_errors_doc)]
+impl DaemonStream<tokio_rustls::client::TlsStream<TcpStream>> {
+    /// Like [`Self::connect`], but speaks TLS to `host` before the BOINC
+    /// handshake.
+    ///
+    /// The BOINC daemon itself only talks plaintext: this is for daemons
+    /// exposed through a TLS-terminating proxy such as stunnel. The proxy's
+    /// certificate is checked against the host part of `host`; an unusable
+    /// host name fails with [`Error::InvalidURL`].
+    pub async fn connect_tls(
+        host: String,
+        password: Option<String>,
+        tls_config: std::sync::Arc<tokio_rustls::rustls::ClientConfig>,
+    ) -> Result<Self, Error> {
+        let name = host
+            .rsplit_once(':')
+            .map_or(host.as_str(), |(name, _)| name);
+        let name = tokio_rustls::rustls::pki_types::ServerName::try_from(
+            name.trim_start_matches('[')
+                .trim_end_matches(']')
+                .to_owned(),
+        )
+        .map_err(|e| Error::InvalidURL(format!("{name}: {e}")))?;
+
+        let io = TcpStream::connect(&host).await?;
+        let io = tokio_rustls::TlsConnector::from(tls_config)
+            .connect(name, io)
+            .await?;
+        Self::authenticate(io, password).await
+    }
+}
+
+#[allow(clippy::missing_errors_doc)]
 impl<Io: AsyncRead + AsyncWrite + Unpin> DaemonStream<Io> {
+    /// How long the daemon gets to complete the authentication handshake.
+    pub const AUTH_TIMEOUT: Duration = Duration::from_secs(30);
+
+    /// Authenticates over a stream the caller has already opened, such as a
+    /// Unix socket or a tunnel.
+    pub async fn connect_io(io: Io, password: Option<String>) -> Result<Self, Error> {
+        Self::authenticate(io, password).await
+    }
+
+    /// Runs the handshake, failing with [`Error::InvalidPassword`] if the
+    /// daemon rejects the password and [`Error::Timeout`] if it stops
+    /// answering.
+    ///
+    /// Without a password the handshake is skipped: the daemon still answers
+    /// read-only RPCs such as `exchange_versions` from local connections, and
+    /// rejects the rest with `<unauthorized/>`, surfaced as [`Error::Auth`].
     async fn authenticate(io: Io, password: Option<String>) -> Result<Self, Error> {
+        Self::authenticate_with(io, password, Md5Hasher).await
+    }
+
+    async fn authenticate_with<H: AuthHasher>(
+        io: Io,
+        password: Option<String>,
+        hasher: H,
+    ) -> Result<Self, Error> {
+        let Some(password) = password else {
+            return Ok(Self {
+                conn: BoincCodec::new(CodecMode::Client).framed(io),
+            });
+        };
+        tokio::time::timeout(Self::AUTH_TIMEOUT, Self::handshake(io, password, hasher))
+            .await
+            .map_err(|_| Error::Timeout("Daemon did not complete authentication".into()))?
+    }
+
+    async fn handshake<H: AuthHasher>(io: Io, password: String, hasher: H) -> Result<Self, Error> {
         let mut conn = BoincCodec::new(CodecMode::Client).framed(io);
 
         let mut out = Some(vec![treexml::Element::new("auth1")]);
@@ -150,15 +362,14 @@ impl<Io: AsyncRead + AsyncWrite + Unpin> DaemonStream<Io> {
                                 ));
                             }
                             let mut nonce_node = treexml::Element::new("nonce_hash");
-                            let pwd = password.clone().ok_or_else(|| {
-                                Error::Auth("Password required for nonce".to_string())
-                            })?;
-                            nonce_node.text = Some(compute_nonce_hash(
-                                &pwd,
-                                &node
-                                    .text
-                                    .ok_or_else(|| Error::Auth("Invalid nonce".into()))?,
-                            ));
+                            nonce_node.text = Some(
+                                hasher.nonce_hash(
+                                    &node
+                                        .text
+                                        .ok_or_else(|| Error::Auth("Invalid nonce".into()))?,
+                                    &password,
+                                ),
+                            );
 
                             let mut auth2_node = treexml::Element::new("auth2");
                             auth2_node.children.push(nonce_node);
@@ -166,6 +377,9 @@ impl<Io: AsyncRead + AsyncWrite + Unpin> DaemonStream<Io> {
                             out = Some(vec![auth2_node]);
                             nonce_sent = true;
                         }
+                        "unauthorized" if nonce_sent => {
+                            return Err(Error::InvalidPassword("unauthorized".to_string()));
+                        }
                         "unauthorized" => {
                             return Err(Error::Auth("unauthorized".to_string()));
                         }
@@ -192,7 +406,15 @@ impl<Io: AsyncRead + AsyncWrite + Unpin> DaemonStream<Io> {
         }
     }
 
-    pub(crate) async fn query(
+    /// Sends one request and waits for its reply, without going through
+    /// [`crate::Transport`].
+    ///
+    /// `request_data` holds the children of the request, e.g.
+    /// `[<get_cc_status/>]`: the codec adds the `<boinc_gui_rpc_request>`
+    /// wrapper and terminator, and the returned elements are the children of
+    /// `<boinc_gui_rpc_reply>`. The reply is returned as-is; daemon errors
+    /// such as `<error>` are not turned into [`Error`]s here.
+    pub async fn query(
         &mut self,
         request_data: Vec<treexml::Element>,
     ) -> Result<Vec<treexml::Element>, Error> {
@@ -236,3 +458,324 @@ impl<Io: AsyncRead + AsyncWrite + Unpin> DaemonStream<Io> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{AuthHasher, BoincCodec, CodecMode, DaemonStream, Md5Hasher, TERMCHAR};
+    use crate::errors::Error;
+    use bytes::BytesMut;
+    use futures::{SinkExt, TryStreamExt};
+    use tokio::{io::AsyncWriteExt, net::TcpStream};
+    use tokio_util::codec::{Decoder, Encoder};
+
+    fn large_reply(n: usize) -> Vec<treexml::Element> {
+        let mut results = treexml::Element::new("results");
+        for i in 0..n {
+            let mut result = treexml::Element::new("result");
+            let mut name = treexml::Element::new("name");
+            name.text = Some(format!(
+                "h1_0{i:04}_O3aLIGO_run1__longer_name_for_padding_0"
+            ));
+            result.children.push(name);
+            results.children.push(result);
+        }
+        vec![results]
+    }
+
+    #[test]
+    fn framing_round_trip() {
+        let mut buf = BytesMut::new();
+        BoincCodec::new(CodecMode::Client)
+            .encode(vec![treexml::Element::new("get_state")], &mut buf)
+            .unwrap();
+        assert!(buf.starts_with(b"<boinc_gui_rpc_request>"));
+        assert!(buf.ends_with(b"</boinc_gui_rpc_request>\x03"));
+        assert_eq!(DaemonStream::<TcpStream>::REQUEST_END, TERMCHAR);
+
+        let decoded = BoincCodec::new(CodecMode::Server)
+            .decode(&mut buf)
+            .unwrap()
+            .unwrap();
+        assert_eq!(decoded.len(), 1);
+        assert_eq!(decoded[0].name, "get_state");
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn decode_sections() {
+        let mut buf = BytesMut::from(
+            &b"<?xml version=\"1.0\"?>\n<boinc_gui_rpc_reply><client_state>\
+               <host_info><p_ncpus>8</p_ncpus></host_info>\
+               <project><master_url>https://einstein.phys.uwm.edu/</master_url></project>\
+               <app><name>einstein_O3AS</name></app>\
+               <result><name>h1_0001</name><platform>x86_64-pc-linux-gnu</platform></result>\
+               <result><name>h1_&amp;0002</name><ready_to_report/></result>\
+               </client_state></boinc_gui_rpc_reply>\x03"[..],
+        );
+        let decoded = BoincCodec::new(CodecMode::Client)
+            .with_sections(&["project", "result"])
+            .decode(&mut buf)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(decoded.len(), 1);
+        let state = &decoded[0];
+        let names = state.children.iter().map(|e| &*e.name).collect::<Vec<_>>();
+        assert_eq!(names, ["project", "result", "result"]);
+        assert_eq!(
+            state.children[1].children[1].text.as_deref(),
+            Some("x86_64-pc-linux-gnu")
+        );
+        assert_eq!(
+            state.children[2].children[0].text.as_deref(),
+            Some("h1_&0002")
+        );
+        assert_eq!(state.children[2].children[1].name, "ready_to_report");
+
+        // Status replies are kept even though they are not sections.
+        let mut buf = BytesMut::from(
+            &b"<boinc_gui_rpc_reply><unauthorized/><error>bad</error></boinc_gui_rpc_reply>\x03"[..],
+        );
+        let decoded = BoincCodec::new(CodecMode::Client)
+            .with_sections(&["result"])
+            .decode(&mut buf)
+            .unwrap()
+            .unwrap();
+        let names = decoded.iter().map(|e| &*e.name).collect::<Vec<_>>();
+        assert_eq!(names, ["unauthorized", "error"]);
+        assert_eq!(decoded[1].text.as_deref(), Some("bad"));
+
+        // A client codec picks the sections for `get_state` on its own.
+        let mut codec = BoincCodec::new(CodecMode::Client);
+        codec
+            .encode(
+                vec![treexml::Element::new("get_state")],
+                &mut BytesMut::new(),
+            )
+            .unwrap();
+        let mut buf = BytesMut::from(
+            &b"<boinc_gui_rpc_reply><client_state>\
+               <app><name>einstein_O3AS</name></app>\
+               <result><name>h1_0001</name></result>\
+               </client_state></boinc_gui_rpc_reply>\x03"[..],
+        );
+        let decoded = codec.decode(&mut buf).unwrap().unwrap();
+        let names = decoded[0]
+            .children
+            .iter()
+            .map(|e| &*e.name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["result"]);
+
+        let mut buf = BytesMut::from(&b"<boinc_gui_rpc_request></boinc_gui_rpc_request>\x03"[..]);
+        assert!(BoincCodec::new(CodecMode::Client)
+            .with_sections(&["result"])
+            .decode(&mut buf)
+            .is_err());
+    }
+
+    #[test]
+    fn decode_in_chunks() {
+        let mut encoded = BytesMut::new();
+        BoincCodec::new(CodecMode::Server)
+            .encode(large_reply(500), &mut encoded)
+            .unwrap();
+        assert!(encoded.len() > 8192);
+
+        let mut codec = BoincCodec::new(CodecMode::Client);
+        let mut buf = BytesMut::new();
+        let mut decoded = None;
+        for chunk in encoded.chunks(1024) {
+            assert!(decoded.is_none());
+            buf.extend_from_slice(chunk);
+            decoded = codec.decode(&mut buf).unwrap();
+        }
+        assert_eq!(decoded.unwrap()[0].children.len(), 500);
+    }
+
+    #[test]
+    fn decode_tolerates_prolog() {
+        for reply in [
+            &b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\" ?>\n<boinc_gui_rpc_reply><success/></boinc_gui_rpc_reply>\x03"[..],
+            &b"\xef\xbb\xbf<boinc_gui_rpc_reply><success/></boinc_gui_rpc_reply>\x03"[..],
+            &b"\n  <boinc_gui_rpc_reply>\n<success/>\n</boinc_gui_rpc_reply>\n\n\x03"[..],
+        ] {
+            let decoded = BoincCodec::new(CodecMode::Client)
+                .decode(&mut BytesMut::from(reply))
+                .unwrap()
+                .unwrap();
+            assert_eq!(decoded[0].name, "success");
+        }
+    }
+
+    #[tokio::test]
+    async fn query_large_reply() {
+        let (client_io, server_io) = tokio::io::duplex(1024);
+        let mut stream = DaemonStream {
+            conn: BoincCodec::new(CodecMode::Client).framed(client_io),
+        };
+        let server = tokio::spawn(async move {
+            let mut conn = BoincCodec::new(CodecMode::Server).framed(server_io);
+            conn.try_next().await.unwrap().unwrap();
+            conn.send(large_reply(500)).await.unwrap();
+        });
+
+        let reply = stream
+            .query(vec![treexml::Element::new("get_results")])
+            .await
+            .unwrap();
+        assert_eq!(reply[0].children.len(), 500);
+        server.await.unwrap();
+    }
+
+    #[test]
+    fn decode_unexpected_root() {
+        let mut buf =
+            BytesMut::from(&b"<html><head><title>502 Bad Gateway</title></head></html>\x03"[..]);
+        assert_eq!(
+            BoincCodec::new(CodecMode::Client).decode(&mut buf),
+            Err(Error::DataParse(
+                "unexpected reply root: <html>, expected <boinc_gui_rpc_reply>".into()
+            ))
+        );
+    }
+
+    #[test]
+    fn decode_complete_reply_over_limit() {
+        let mut buf = BytesMut::new();
+        BoincCodec::new(CodecMode::Server)
+            .encode(large_reply(500), &mut buf)
+            .unwrap();
+
+        let res = BoincCodec::new(CodecMode::Client)
+            .with_max_reply_size(1024)
+            .decode(&mut buf);
+        assert!(matches!(res, Err(Error::DataParse(e)) if e.starts_with("Reply too large")));
+    }
+
+    #[cfg(feature = "tls")]
+    #[tokio::test]
+    async fn connect_tls_invalid_host() {
+        let config = tokio_rustls::rustls::ClientConfig::builder()
+            .with_root_certificates(tokio_rustls::rustls::RootCertStore::empty())
+            .with_no_client_auth();
+        let res = DaemonStream::connect_tls("not a host:31416".into(), None, config.into()).await;
+        assert!(matches!(res, Err(Error::InvalidURL(_))));
+    }
+
+    #[tokio::test]
+    async fn query_reply_size_limit() {
+        let (client_io, mut server_io) = tokio::io::duplex(1024);
+        let mut stream = DaemonStream {
+            conn: BoincCodec::new(CodecMode::Client).framed(client_io),
+        };
+        stream.set_max_reply_size(16 * 1024);
+        tokio::spawn(async move {
+            let junk = [b'x'; 1024];
+            while server_io.write_all(&junk).await.is_ok() {}
+        });
+
+        let res = stream
+            .query(vec![treexml::Element::new("get_results")])
+            .await;
+        assert!(matches!(res, Err(Error::DataParse(e)) if e.starts_with("Reply too large")));
+    }
+
+    #[tokio::test]
+    async fn authenticate_wrong_password() {
+        let (client_io, server_io) = tokio::io::duplex(1024);
+        let server = tokio::spawn(async move {
+            let mut conn = BoincCodec::new(CodecMode::Server).framed(server_io);
+            conn.try_next().await.unwrap().unwrap();
+            let mut nonce = treexml::Element::new("nonce");
+            nonce.text = Some("1700000000.123456".into());
+            conn.send(vec![nonce]).await.unwrap();
+            let req = conn.try_next().await.unwrap().unwrap();
+            assert_eq!(req[0].name, "auth2");
+            conn.send(vec![treexml::Element::new("unauthorized")])
+                .await
+                .unwrap();
+            // Keep the connection open: the client must not wait for more.
+            conn
+        });
+
+        let res = DaemonStream::authenticate(client_io, Some("wrong".into())).await;
+        assert!(matches!(res, Err(Error::InvalidPassword(_))));
+        drop(server.await.unwrap());
+    }
+
+    #[test]
+    fn md5_nonce_hash() {
+        assert_eq!(
+            Md5Hasher.nonce_hash("1700000000.123456", "secret"),
+            "337cf11a9e80ca33465d901f665f82ee"
+        );
+    }
+
+    #[tokio::test]
+    async fn authenticate_with_custom_hasher() {
+        struct Reversed;
+
+        impl AuthHasher for Reversed {
+            fn nonce_hash(&self, nonce: &str, password: &str) -> String {
+                format!("{password}{nonce}").chars().rev().collect()
+            }
+        }
+
+        let (client_io, server_io) = tokio::io::duplex(1024);
+        let server = tokio::spawn(async move {
+            let mut conn = BoincCodec::new(CodecMode::Server).framed(server_io);
+            conn.try_next().await.unwrap().unwrap();
+            let mut nonce = treexml::Element::new("nonce");
+            nonce.text = Some("42".into());
+            conn.send(vec![nonce]).await.unwrap();
+            let req = conn.try_next().await.unwrap().unwrap();
+            assert_eq!(req[0].children[0].text.as_deref(), Some("24wp"));
+            conn.send(vec![treexml::Element::new("authorized")])
+                .await
+                .unwrap();
+            conn
+        });
+
+        DaemonStream::authenticate_with(client_io, Some("pw".into()), Reversed)
+            .await
+            .unwrap();
+        drop(server.await.unwrap());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn authenticate_times_out() {
+        let (client_io, server_io) = tokio::io::duplex(1024);
+        let res = DaemonStream::authenticate(client_io, Some("pw".into())).await;
+        assert!(matches!(res, Err(Error::Timeout(_))));
+        drop(server_io);
+    }
+
+    #[test]
+    fn encode_escapes_special_characters() {
+        let mut node = treexml::Element::new("product_name");
+        node.text = Some("Dell & Sons <Inspiron>".into());
+
+        let mut buf = BytesMut::new();
+        BoincCodec::new(CodecMode::Client)
+            .encode(vec![node], &mut buf)
+            .unwrap();
+        let wire = String::from_utf8_lossy(&buf);
+        assert!(wire.contains("Dell &amp; Sons &lt;Inspiron&gt;"));
+
+        let decoded = BoincCodec::new(CodecMode::Server)
+            .decode(&mut buf)
+            .unwrap()
+            .unwrap();
+        assert_eq!(decoded[0].text.as_deref(), Some("Dell & Sons <Inspiron>"));
+    }
+
+    #[test]
+    fn encode_unrepresentable_text_is_an_error() {
+        let mut node = treexml::Element::new("product_name");
+        node.text = Some("\u{1F600}".into());
+        assert!(BoincCodec::new(CodecMode::Client)
+            .encode(vec![node], &mut BytesMut::new())
+            .is_err());
+    }
+}
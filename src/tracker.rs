@@ -0,0 +1,153 @@
+use crate::{
+    errors::Error,
+    models::{ResultKey, ResultState, TaskResult},
+    BoincApi,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResultEvent {
+    Added {
+        key: ResultKey,
+        state: Option<ResultState>,
+    },
+    StateChanged {
+        key: ResultKey,
+        from: Option<ResultState>,
+        to: Option<ResultState>,
+    },
+    /// The task finished computing and its output is being or has been uploaded.
+    Completed {
+        key: ResultKey,
+    },
+    /// The task failed to compute or upload.
+    Errored {
+        key: ResultKey,
+    },
+    Removed {
+        key: ResultKey,
+    },
+}
+
+/// Turns repeated `get_results` polls into a stream of [`ResultEvent`]s.
+#[derive(Clone, Debug, Default)]
+pub struct ResultTracker {
+    states: BTreeMap<ResultKey, Option<ResultState>>,
+}
+
+impl ResultTracker {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a new snapshot and returns what changed since the previous one.
+    ///
+    /// Results without a project URL or name are ignored.
+    pub fn observe(&mut self, results: &[TaskResult]) -> Vec<ResultEvent> {
+        let mut events = Vec::new();
+        let mut states = BTreeMap::new();
+        for result in results {
+            let Some(key) = result.key() else {
+                continue;
+            };
+            let to = result.result_state();
+            match self.states.get(&key) {
+                None => events.push(ResultEvent::Added {
+                    key: key.clone(),
+                    state: to,
+                }),
+                Some(&from) if from != to => {
+                    events.push(ResultEvent::StateChanged {
+                        key: key.clone(),
+                        from,
+                        to,
+                    });
+                    match to {
+                        Some(ResultState::FilesUploading | ResultState::FilesUploaded)
+                            if !matches!(
+                                from,
+                                Some(ResultState::FilesUploading | ResultState::FilesUploaded)
+                            ) =>
+                        {
+                            events.push(ResultEvent::Completed { key: key.clone() });
+                        }
+                        Some(ResultState::ComputeError | ResultState::UploadFailed) => {
+                            events.push(ResultEvent::Errored { key: key.clone() });
+                        }
+                        _ => {}
+                    }
+                }
+                Some(_) => {}
+            }
+            states.insert(key, to);
+        }
+        for key in self.states.keys() {
+            if !states.contains_key(key) {
+                events.push(ResultEvent::Removed { key: key.clone() });
+            }
+        }
+        self.states = states;
+        events
+    }
+
+    /// Polls `api` for all results and returns the events since the last update.
+    #[allow(clippy::future_not_send, clippy::missing_errors_doc)]
+    pub async fn update<A: BoincApi + ?Sized>(
+        &mut self,
+        api: &mut A,
+    ) -> Result<Vec<ResultEvent>, Error> {
+        let results = api.get_results(false).await?;
+        Ok(self.observe(&results))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ResultEvent, ResultTracker};
+    use crate::models::{ResultKey, ResultState, TaskResult};
+
+    #[test]
+    fn result_tracker_completion() {
+        let result = |name: &str, state: i64| TaskResult {
+            name: Some(name.into()),
+            project_url: Some("https://einstein.phys.uwm.edu/".into()),
+            state: Some(state),
+            ..Default::default()
+        };
+        let key = |name: &str| ResultKey {
+            project_url: "https://einstein.phys.uwm.edu/".into(),
+            name: name.into(),
+        };
+
+        let mut tracker = ResultTracker::new();
+        assert_eq!(
+            tracker.observe(&[result("a", 2), result("b", 2)]),
+            vec![
+                ResultEvent::Added {
+                    key: key("a"),
+                    state: Some(ResultState::FilesDownloaded),
+                },
+                ResultEvent::Added {
+                    key: key("b"),
+                    state: Some(ResultState::FilesDownloaded),
+                },
+            ]
+        );
+        assert_eq!(
+            tracker.observe(&[result("a", 4)]),
+            vec![
+                ResultEvent::StateChanged {
+                    key: key("a"),
+                    from: Some(ResultState::FilesDownloaded),
+                    to: Some(ResultState::FilesUploading),
+                },
+                ResultEvent::Completed { key: key("a") },
+                ResultEvent::Removed { key: key("b") },
+            ]
+        );
+        assert!(tracker.observe(&[result("a", 4)]).is_empty());
+    }
+}
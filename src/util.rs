@@ -8,6 +8,170 @@ pub fn parse_node(s: &str) -> Result<treexml::Element, Error> {
     doc.root.ok_or_else(|| Error::Null("Root is empty".into()))
 }
 
+/// Parses only the elements named in `sections`, wherever they occur, without
+/// building the rest of the document.
+///
+/// Matching sections are kept whole, along with the elements enclosing them,
+/// so the result has the shape of the full document minus everything else.
+/// Children of the root are always kept, if only with their text, so status
+/// replies such as `<error>` or `<unauthorized/>` survive.
+/// Meant for large replies such as `get_state`, where [`parse_node`] would
+/// materialize every node.
+pub fn parse_sections(s: &str, sections: &[&str]) -> Result<treexml::Element, Error> {
+    use quick_xml::events::{BytesStart, Event};
+
+    fn element(start: &BytesStart<'_>) -> Result<treexml::Element, quick_xml::Error> {
+        let name = start.name();
+        let mut e = treexml::Element::new(String::from_utf8_lossy(name.local_name().as_ref()));
+        e.prefix = name
+            .prefix()
+            .map(|p| String::from_utf8_lossy(p.as_ref()).into_owned());
+        for attr in start.attributes() {
+            let attr = attr?;
+            e.attributes.insert(
+                String::from_utf8_lossy(attr.key.as_ref()).into_owned(),
+                attr.unescape_value()?.into_owned(),
+            );
+        }
+        Ok(e)
+    }
+
+    /// An open element; `whole` once inside a matching section.
+    struct Frame {
+        e: treexml::Element,
+        whole: bool,
+    }
+
+    /// Attaches a closed element to its parent if it is worth keeping, or
+    /// makes it the root.
+    fn close(open: &mut [Frame], root: &mut Option<treexml::Element>, frame: Frame) {
+        let is_root_child = open.len() == 1;
+        match open.last_mut() {
+            Some(parent) => {
+                if frame.whole || !frame.e.children.is_empty() || is_root_child {
+                    parent.e.children.push(frame.e);
+                }
+            }
+            None => *root = Some(frame.e),
+        }
+    }
+
+    let mut reader = quick_xml::Reader::from_str(s);
+    let mut root: Option<treexml::Element> = None;
+    // Open elements, outermost (the root) first.
+    let mut open: Vec<Frame> = Vec::new();
+    loop {
+        let event = reader.read_event()?;
+        let (start, empty) = match &event {
+            Event::Start(start) => (start, false),
+            Event::Empty(start) => (start, true),
+            Event::End(_) => {
+                if let Some(frame) = open.pop() {
+                    close(&mut open, &mut root, frame);
+                }
+                continue;
+            }
+            Event::Text(text) => {
+                if let Some(frame) = open.last_mut() {
+                    let text = text.unescape()?;
+                    if !text.trim().is_empty() {
+                        frame.e.text.get_or_insert_with(String::new).push_str(&text);
+                    }
+                }
+                continue;
+            }
+            Event::CData(cdata) => {
+                if let Some(frame) = open.last_mut() {
+                    frame
+                        .e
+                        .cdata
+                        .get_or_insert_with(String::new)
+                        .push_str(&cdata.decode().map_err(quick_xml::Error::from)?);
+                }
+                continue;
+            }
+            Event::Eof => break,
+            _ => continue,
+        };
+        if root.is_some() {
+            // Anything after the root element is not part of the document.
+            continue;
+        }
+        let whole = !open.is_empty()
+            && (open.last().is_some_and(|f| f.whole)
+                || sections.contains(&&*String::from_utf8_lossy(start.local_name().as_ref())));
+        let frame = Frame {
+            e: element(start)?,
+            whole,
+        };
+        if empty {
+            close(&mut open, &mut root, frame);
+        } else {
+            open.push(frame);
+        }
+    }
+
+    root.ok_or_else(|| Error::Null("Root is empty".into()))
+}
+
+/// Strips what may precede the root element of a reply: surrounding
+/// whitespace, a byte order mark (also as decoded from ISO-8859-1) and an
+/// `<?xml ...?>` declaration.
+pub fn strip_prolog(s: &str) -> &str {
+    let s = s.trim();
+    let s = s
+        .strip_prefix('\u{feff}')
+        .or_else(|| s.strip_prefix("\u{ef}\u{bb}\u{bf}"))
+        .unwrap_or(s)
+        .trim_start();
+    match s
+        .strip_prefix("<?xml")
+        .and_then(|rest| rest.split_once("?>"))
+    {
+        Some((_, rest)) => rest.trim_start(),
+        None => s,
+    }
+}
+
+/// Canonicalizes a project master URL the way the client does when attaching.
+///
+/// Project-scoped requests only match when the URL is identical, so `http://`
+/// is assumed when no scheme is given, the scheme is lowercased and a
+/// trailing slash is added. The host keeps its casing: the client stores the
+/// URL as given and compares it byte for byte.
+#[must_use]
+pub fn canonicalize_master_url(url: &str) -> String {
+    let url = url.trim();
+    let (scheme, rest) = url.split_once("://").unwrap_or(("http", url));
+    let mut out = format!("{}://{rest}", scheme.to_ascii_lowercase());
+    if !out.ends_with('/') {
+        out.push('/');
+    }
+    out
+}
+
+/// Whether two master URLs name the same project, ignoring the casing of
+/// the scheme and host, which the client itself does not.
+pub fn same_master_url(a: &str, b: &str) -> bool {
+    fn split(url: &str) -> (String, String) {
+        let url = canonicalize_master_url(url);
+        let (scheme, rest) = url.split_once("://").unwrap_or_default();
+        let (host, path) = rest.find('/').map_or((rest, ""), |i| rest.split_at(i));
+        (
+            format!("{scheme}://{}", host.to_ascii_lowercase()),
+            path.into(),
+        )
+    }
+    split(a) == split(b)
+}
+
+/// Serializes a node to XML text without the document declaration.
+pub fn serialize_node(node: &treexml::Element) -> String {
+    format!("{node}")
+        .replace("<?xml version='1.0'?>", "")
+        .replace(" />", "/>")
+}
+
 pub fn eval_node_contents<T>(node: &treexml::Element) -> Option<T>
 where
     T: FromStr,
@@ -17,6 +181,16 @@ where
         .map_or_else(|| None, |v| v.parse::<T>().ok())
 }
 
+/// Parses a BOINC boolean, which may be numeric (`0`/`1`), textual, or a bare
+/// `<flag/>` tag meaning `true`.
+pub fn eval_bool(node: &treexml::Element) -> Option<bool> {
+    match node.text.as_deref().map(str::trim) {
+        None | Some("" | "true") => Some(true),
+        Some("false") => Some(false),
+        Some(v) => v.parse::<i64>().ok().map(|v| v != 0),
+    }
+}
+
 pub fn any_text(node: &treexml::Element) -> Option<String> {
     if node.cdata.is_some() {
         return node.cdata.clone();
@@ -27,6 +201,23 @@ pub fn any_text(node: &treexml::Element) -> Option<String> {
     None
 }
 
+#[allow(clippy::ref_option)]
 pub fn trimmed_optional(e: &Option<String>) -> Option<String> {
-    e.clone().map(|v| v.trim().into())
+    e.as_deref().map(|v| v.trim().to_owned())
+}
+
+/// The current day number as BOINC counts it: whole days since the Unix epoch.
+pub fn current_day() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| {
+            i64::try_from(d.as_secs() / 86400).unwrap_or(i64::MAX)
+        })
+}
+
+/// Converts a byte count that BOINC reports as a float. Negative, NaN and
+/// out-of-range values yield `None`.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub fn bytes_from_f64(v: f64) -> Option<u64> {
+    (v.is_finite() && v >= 0.0 && v < 2f64.powi(64)).then(|| v.round() as u64)
 }